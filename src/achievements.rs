@@ -0,0 +1,321 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+use super::*;
+use gtk4::{Revealer, RevealerTransitionType};
+const TOAST_SECONDS: u32 = 4;
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Achievement {
+    FirstSolve,
+    NoHints4x4,
+    AtPar,
+    NoFractions,
+    OnlyAddMultiple,
+    Biggest,
+    WeekStreak,
+}
+impl Achievement {
+    pub const ALL: [Self; 7] = [
+        Self::FirstSolve,
+        Self::NoHints4x4,
+        Self::AtPar,
+        Self::NoFractions,
+        Self::OnlyAddMultiple,
+        Self::Biggest,
+        Self::WeekStreak,
+    ];
+    ///The name in the statistics file, which mustn't change
+    pub const fn id(self) -> &'static str {
+        match self {
+            Self::FirstSolve => "first-solve",
+            Self::NoHints4x4 => "no-hints-4x4",
+            Self::AtPar => "at-par",
+            Self::NoFractions => "no-fractions",
+            Self::OnlyAddMultiple => "only-add-multiple",
+            Self::Biggest => "biggest",
+            Self::WeekStreak => "week-streak",
+        }
+    }
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|achievement| achievement.id() == id)
+    }
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::FirstSolve => "First Steps",
+            Self::NoHints4x4 => "On Your Own",
+            Self::AtPar => "Par for the Course",
+            Self::NoFractions => "Whole Numbers Only",
+            Self::OnlyAddMultiple => "Just Add",
+            Self::Biggest => "Go Big",
+            Self::WeekStreak => "Every Day",
+        }
+    }
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::FirstSolve => "Solve a system.",
+            Self::NoHints4x4 => "Solve a 4×4 system without any hints.",
+            Self::AtPar => "Solve a system in no more operations than the computer needs.",
+            Self::NoFractions => "Solve a system without a fraction ever appearing on the board.",
+            Self::OnlyAddMultiple => {
+                "Solve a system only by adding multiples of rows to other rows."
+            }
+            Self::Biggest => "Solve an 8×8 system.",
+            Self::WeekStreak => "Solve the daily puzzle 7 days in a row.",
+        }
+    }
+}
+///What happened while solving one system, for deciding whether it counts and which achievements
+///it earns
+pub struct SolveRecord {
+    ///Whether the computer helped, in the solver window or the tutorial
+    pub assisted: bool,
+    ///Whether the system was made of real numbers
+    pub real: bool,
+    pub size: usize,
+    pub moves: usize,
+    pub par: usize,
+    pub hints: u64,
+    ///Whether a number that isn't whole was ever on the board
+    pub fraction_seen: bool,
+    pub only_add_multiple: bool,
+    pub daily_streak: u64,
+}
+impl SolveRecord {
+    ///Whether the solve counts toward the statistics and achievements at all
+    pub fn counts(&self) -> bool {
+        !self.assisted && self.real
+    }
+    fn earns(&self, achievement: Achievement) -> bool {
+        self.counts()
+            && match achievement {
+                Achievement::FirstSolve => true,
+                Achievement::NoHints4x4 => self.size == 4 && self.hints == 0,
+                Achievement::AtPar => self.moves <= self.par,
+                Achievement::NoFractions => !self.fraction_seen,
+                Achievement::OnlyAddMultiple => self.only_add_multiple,
+                Achievement::Biggest => self.size == MAX_SYSTEM_SIZE,
+                Achievement::WeekStreak => self.daily_streak >= 7,
+            }
+    }
+}
+///Checks if every operation added a multiple of a row to another.
+pub fn only_adds_multiples(operations: &[RowOperation]) -> bool {
+    operations
+        .iter()
+        .all(|operation| matches!(operation, RowOperation::MakeCoefficient0WithRow(..)))
+}
+///Checks for a number on the board that isn't whole.
+pub fn has_fraction(system: &System) -> bool {
+    system.equations.iter().any(|equation| {
+        equation
+            .coefficients
+            .iter()
+            .chain(std::iter::once(&equation.solution))
+            .any(|number| number.fract() != 0.0)
+    })
+}
+///Makes the notification that slides up over the bottom of the board when an achievement is
+///unlocked. It's hidden until `Game::show_toast`.
+pub fn build_toast() -> Revealer {
+    let label = Label::builder().wrap(true).max_width_chars(40).build();
+    label.add_css_class("app-notification");
+    Revealer::builder()
+        .child(&label)
+        .transition_type(RevealerTransitionType::SlideUp)
+        .halign(gtk4::Align::Center)
+        .valign(gtk4::Align::End)
+        .can_target(false)
+        .build()
+}
+impl Game {
+    ///Keeps track of whether a fraction has been on the board for achievements. Called for every
+    ///row operation after it's done.
+    pub(crate) fn track_operation(&self) {
+        if self.has_fraction() {
+            self.fraction_seen.set(true);
+        }
+    }
+    ///Unlocks whatever achievements `record` earns that aren't unlocked yet and announces them.
+    pub(crate) fn unlock_achievements(&self, stats: &mut Stats, record: &SolveRecord) {
+        let unlocked: Vec<Achievement> = Achievement::ALL
+            .into_iter()
+            .filter(|achievement| {
+                !stats.achievements.contains(achievement) && record.earns(*achievement)
+            })
+            .collect();
+        if unlocked.is_empty() {
+            return;
+        }
+        stats.achievements.extend(unlocked.iter().copied());
+        let names: Vec<&str> = unlocked
+            .iter()
+            .map(|achievement| tr(achievement.name()))
+            .collect();
+        self.show_toast(&tr_format("Achievement unlocked: {}", &[&names.join(", ")]));
+    }
+    pub fn show_toast(&self, text: &str) {
+        if let Some(label) = self.toast.child().and_downcast::<Label>() {
+            label.set_label(text);
+        }
+        self.toast.set_reveal_child(true);
+        //Only the newest toast hides it so that an older one's timer doesn't cut it short.
+        let generation = self.toast_generation.get() + 1;
+        self.toast_generation.set(generation);
+        let toast = self.toast.clone();
+        let toast_generation = Rc::clone(&self.toast_generation);
+        glib::timeout_add_seconds_local_once(TOAST_SECONDS, move || {
+            if toast_generation.get() == generation {
+                toast.set_reveal_child(false);
+            }
+        });
+    }
+}
+///Lists every achievement and whether it's unlocked for the Help window.
+pub fn describe_achievements(stats: &Stats) -> String {
+    Achievement::ALL
+        .into_iter()
+        .map(|achievement| {
+            let mark = if stats.achievements.contains(&achievement) {
+                "✓"
+            } else {
+                "🔒"
+            };
+            format!(
+                "{} {}: {}",
+                mark,
+                tr(achievement.name()),
+                tr(achievement.description())
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Achievement::*;
+    ///A 4×4 system solved at par without hints, fractions, or help, only by adding multiples
+    fn record() -> SolveRecord {
+        SolveRecord {
+            assisted: false,
+            real: true,
+            size: 4,
+            moves: 6,
+            par: 6,
+            hints: 0,
+            fraction_seen: false,
+            only_add_multiple: true,
+            daily_streak: 0,
+        }
+    }
+    fn earned(record: &SolveRecord) -> Vec<Achievement> {
+        Achievement::ALL
+            .into_iter()
+            .filter(|achievement| record.earns(*achievement))
+            .collect()
+    }
+    #[test]
+    fn earns_achievements() {
+        assert_eq!(
+            earned(&record()),
+            [FirstSolve, NoHints4x4, AtPar, NoFractions, OnlyAddMultiple]
+        );
+        let record = SolveRecord {
+            size: MAX_SYSTEM_SIZE,
+            moves: 100,
+            hints: 3,
+            fraction_seen: true,
+            only_add_multiple: false,
+            daily_streak: 7,
+            ..record()
+        };
+        assert_eq!(earned(&record), [FirstSolve, Biggest, WeekStreak]);
+    }
+    #[test]
+    fn needs_each_condition() {
+        let hinted = SolveRecord {
+            hints: 1,
+            ..record()
+        };
+        assert!(!hinted.earns(NoHints4x4));
+        let smaller = SolveRecord {
+            size: 3,
+            ..record()
+        };
+        assert!(!smaller.earns(NoHints4x4));
+        assert!(!smaller.earns(Biggest));
+        let over_par = SolveRecord {
+            moves: 7,
+            ..record()
+        };
+        assert!(!over_par.earns(AtPar));
+        let under_par = SolveRecord {
+            moves: 5,
+            ..record()
+        };
+        assert!(under_par.earns(AtPar));
+        let fraction = SolveRecord {
+            fraction_seen: true,
+            ..record()
+        };
+        assert!(!fraction.earns(NoFractions));
+        let scaled = SolveRecord {
+            only_add_multiple: false,
+            ..record()
+        };
+        assert!(!scaled.earns(OnlyAddMultiple));
+        let six_days = SolveRecord {
+            daily_streak: 6,
+            ..record()
+        };
+        assert!(!six_days.earns(WeekStreak));
+    }
+    #[test]
+    fn earns_nothing_with_help() {
+        let assisted = SolveRecord {
+            assisted: true,
+            daily_streak: 7,
+            ..record()
+        };
+        assert!(!assisted.counts());
+        assert_eq!(earned(&assisted), []);
+        let modular = SolveRecord {
+            real: false,
+            ..record()
+        };
+        assert!(!modular.counts());
+        assert_eq!(earned(&modular), []);
+    }
+    #[test]
+    fn keeps_track_of_what_was_done() {
+        assert!(only_adds_multiples(&[]));
+        assert!(only_adds_multiples(&[
+            RowOperation::MakeCoefficient0WithRow(1, 0, 0),
+            RowOperation::MakeCoefficient0WithRow(0, 1, 1),
+        ]));
+        assert!(!only_adds_multiples(&[
+            RowOperation::MakeCoefficient0WithRow(1, 0, 0),
+            RowOperation::MakeCoefficient1(1, 1),
+        ]));
+        assert!(!only_adds_multiples(&[RowOperation::SwitchRows(0, 1)]));
+        let mut system = System::new(vec![
+            Equation::new(vec![2.0, 4.0], 6.0),
+            Equation::new(vec![1.0, 3.0], 5.0),
+        ]);
+        assert!(!has_fraction(&system));
+        system.apply(RowOperation::MakeCoefficient0WithRow(1, 0, 0));
+        assert!(!has_fraction(&system));
+        system.apply(RowOperation::MakeCoefficient1(1, 1));
+        assert!(!has_fraction(&system));
+        system.equations[0].solution = 0.5;
+        assert!(has_fraction(&system));
+    }
+    #[test]
+    fn keeps_achievement_ids() {
+        for achievement in Achievement::ALL {
+            assert_eq!(Achievement::from_id(achievement.id()), Some(achievement));
+        }
+        assert_eq!(Achievement::from_id("unknown"), None);
+    }
+}
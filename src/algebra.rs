@@ -1,133 +1,239 @@
 // SPDX-License-Identifier: BSD-3-Clause
 // Copyright 2025 UxuginPython
 use super::*;
-use std::mem::MaybeUninit;
+use rand::Rng;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
 macro_rules! impl_assign {
     ($type_name: ident, $trait_name: ident, $func_name: ident, $rhs: ty, $symbol: tt) => {
         impl $trait_name<$rhs> for $type_name {
             fn $func_name(&mut self, rhs: $rhs) {
-                *self = *self $symbol rhs;
+                *self = std::mem::take(self) $symbol rhs;
             }
         }
-    }
+    };
+    //For types that are generic over a field, which don't all have a default to take out
+    ($type_name: ident<F>, $trait_name: ident, $func_name: ident, $rhs: ty, $symbol: tt) => {
+        impl<F: Field> $trait_name<$rhs> for $type_name<F> {
+            fn $func_name(&mut self, rhs: $rhs) {
+                *self = self.clone() $symbol rhs;
+            }
+        }
+    };
 }
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub struct Equation {
-    pub coefficients: [f64; SYSTEM_SIZE],
-    pub solution: f64,
+///How hard a randomly generated system should be. Harder systems have bigger numbers, and easy
+///systems are made from a whole number answer so that they have one if they can be solved at all.
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
 }
-impl Equation {
-    pub const fn new(coefficients: [f64; SYSTEM_SIZE], solution: f64) -> Self {
-        Self {
-            coefficients: coefficients,
-            solution: solution,
+impl Difficulty {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Easy => "Easy",
+            Self::Medium => "Medium",
+            Self::Hard => "Hard",
         }
     }
-    pub fn random() -> Self {
-        let mut coefficients = [0.0; SYSTEM_SIZE];
-        for i in 0..SYSTEM_SIZE {
-            coefficients[i] = rand::random_range(-5..=5) as f64;
+    fn coefficient_range(self) -> RangeInclusive<i32> {
+        match self {
+            Self::Easy => -3..=3,
+            Self::Medium => -5..=5,
+            Self::Hard => -9..=9,
+        }
+    }
+    ///The range of the solutions for medium and hard and of the answers for easy
+    fn solution_range(self) -> RangeInclusive<i32> {
+        match self {
+            Self::Easy => -5..=5,
+            Self::Medium => -10..=10,
+            Self::Hard => -20..=20,
+        }
+    }
+}
+impl FromStr for Difficulty {
+    type Err = String;
+    fn from_str(string: &str) -> Result<Self, String> {
+        match string.to_ascii_lowercase().as_str() {
+            "easy" => Ok(Self::Easy),
+            "medium" => Ok(Self::Medium),
+            "hard" => Ok(Self::Hard),
+            _ => Err(format!(
+                "unknown difficulty {:?}, expected easy, medium, or hard",
+                string
+            )),
+        }
+    }
+}
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Equation<F = f64> {
+    pub coefficients: Vec<F>,
+    pub solution: F,
+}
+impl<F: Field> Equation<F> {
+    pub const fn new(coefficients: Vec<F>, solution: F) -> Self {
+        Self {
+            coefficients,
+            solution,
         }
-        let solution = rand::random_range(-10..=10) as f64;
-        Self::new(coefficients, solution)
     }
     ///Checks if the coefficient can be made 1 without doing it. Still returns true if the
     ///coefficient is already 1.
-    pub const fn can_make_coefficient_1(&self, index: usize) -> bool {
-        self.coefficients[index] != 0.0
+    pub fn can_make_coefficient_1(&self, index: usize) -> bool {
+        !self.coefficients[index].is_zero()
     }
     ///Like `can_make_coefficient_1` but returns false if it's already 1.
-    pub const fn should_make_coefficient_1(&self, index: usize) -> bool {
-        self.can_make_coefficient_1(index) && self.coefficients[index] != 1.0
+    pub fn should_make_coefficient_1(&self, index: usize) -> bool {
+        self.can_make_coefficient_1(index) && !self.coefficients[index].is_one()
     }
     pub fn make_coefficient_1(&mut self, index: usize) {
         let dividend = self.coefficients[index];
         *self /= dividend;
-        debug_assert_eq!(self.coefficients[index], 1.0);
+        debug_assert!(self.coefficients[index].is_one());
     }
 }
-impl Neg for Equation {
+impl Equation {
+    pub fn random(size: usize, difficulty: Difficulty, rng: &mut impl Rng) -> Self {
+        let coefficients = (0..size)
+            .map(|_| rng.random_range(difficulty.coefficient_range()) as f64)
+            .collect();
+        let solution = rng.random_range(difficulty.solution_range()) as f64;
+        Self::new(coefficients, solution)
+    }
+}
+impl<F: Field> Neg for Equation<F> {
     type Output = Self;
     fn neg(self) -> Self {
-        let mut new_coefficients = [0.0; SYSTEM_SIZE];
-        for i in 0..SYSTEM_SIZE {
-            new_coefficients[i] = -self.coefficients[i];
-        }
+        let new_coefficients = self.coefficients.iter().map(|x| -*x).collect();
         Self::new(new_coefficients, -self.solution)
     }
 }
-impl Add for Equation {
+impl<F: Field> Add for Equation<F> {
     type Output = Self;
     fn add(self, rhs: Self) -> Self {
         let mut new_coefficients = self.coefficients;
-        for i in 0..SYSTEM_SIZE {
-            new_coefficients[i] += rhs.coefficients[i];
+        for (new_coefficient, rhs_coefficient) in new_coefficients.iter_mut().zip(rhs.coefficients)
+        {
+            *new_coefficient = *new_coefficient + rhs_coefficient;
         }
         Self::new(new_coefficients, self.solution + rhs.solution)
     }
 }
-impl_assign!(Equation, AddAssign, add_assign, Self, +);
-impl Sub for Equation {
+impl_assign!(Equation<F>, AddAssign, add_assign, Self, +);
+impl<F: Field> Sub for Equation<F> {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self {
         self + -rhs
     }
 }
-impl_assign!(Equation, SubAssign, sub_assign, Self, -);
-impl Mul<f64> for Equation {
+impl_assign!(Equation<F>, SubAssign, sub_assign, Self, -);
+impl<F: Field> Mul<F> for Equation<F> {
     type Output = Self;
-    fn mul(self, rhs: f64) -> Self {
+    fn mul(self, rhs: F) -> Self {
         let mut new_coefficients = self.coefficients;
-        for i in 0..SYSTEM_SIZE {
-            new_coefficients[i] *= rhs;
+        for coefficient in new_coefficients.iter_mut() {
+            *coefficient = *coefficient * rhs;
         }
         Self::new(new_coefficients, self.solution * rhs)
     }
 }
-impl_assign!(Equation, MulAssign, mul_assign, f64, *);
-impl Div<f64> for Equation {
+impl_assign!(Equation<F>, MulAssign, mul_assign, F, *);
+impl<F: Field> Div<F> for Equation<F> {
     type Output = Self;
-    fn div(self, rhs: f64) -> Self {
+    fn div(self, rhs: F) -> Self {
         let mut new_coefficients = self.coefficients;
-        for i in 0..SYSTEM_SIZE {
-            new_coefficients[i] /= rhs;
+        for coefficient in new_coefficients.iter_mut() {
+            *coefficient = *coefficient / rhs;
         }
         Self::new(new_coefficients, self.solution / rhs)
     }
 }
-impl_assign!(Equation, DivAssign, div_assign, f64, /);
+impl_assign!(Equation<F>, DivAssign, div_assign, F, /);
+///One of the three row operations, with the same arguments as the `System` method it calls.
 #[derive(Clone, Copy, Debug, PartialEq)]
-pub struct System {
-    pub equations: [Equation; SYSTEM_SIZE],
+pub enum RowOperation {
+    SwitchRows(usize, usize),
+    MakeCoefficient1(usize, usize),
+    MakeCoefficient0WithRow(usize, usize, usize),
 }
-impl System {
-    pub const fn new(equations: [Equation; SYSTEM_SIZE]) -> Self {
-        Self {
-            equations: equations,
-        }
+///Where a coefficient is in the elimination, from `System::structure`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CellStatus {
+    ///The pivot of its column, the nonzero coefficient the rest of the column is cleared with
+    Pivot,
+    ///A 0 above or below a pivot
+    Cleared,
+    ///A coefficient above or below a pivot that still has to be made 0
+    ToEliminate,
+    ///In a column without a pivot, which means an unknown that can be anything
+    Free,
+}
+///The pivots of a system and where each coefficient is in the elimination
+#[derive(Clone, Debug, PartialEq)]
+pub struct Structure {
+    ///The column of each row's pivot, if it has one
+    pub pivot_columns: Vec<Option<usize>>,
+    ///The status of each coefficient, row by row
+    pub cells: Vec<Vec<CellStatus>>,
+}
+///A square system with as many equations as unknowns
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct System<F = f64> {
+    pub equations: Vec<Equation<F>>,
+}
+impl<F: Field> System<F> {
+    pub fn new(equations: Vec<Equation<F>>) -> Self {
+        debug_assert!(
+            equations
+                .iter()
+                .all(|equation| equation.coefficients.len() == equations.len())
+        );
+        Self { equations }
     }
-    pub fn random() -> Self {
-        let mut equations = [MaybeUninit::uninit(); SYSTEM_SIZE];
-        for i in 0..SYSTEM_SIZE {
-            equations[i].write(Equation::random());
+    ///The number of equations, which is also the number of unknowns
+    pub fn size(&self) -> usize {
+        self.equations.len()
+    }
+}
+impl System {
+    pub fn random(size: usize, difficulty: Difficulty, rng: &mut impl Rng) -> Self {
+        if difficulty != Difficulty::Easy {
+            return Self::new(
+                (0..size)
+                    .map(|_| Equation::random(size, difficulty, rng))
+                    .collect(),
+            );
         }
-        let equations: [Equation; SYSTEM_SIZE] = unsafe { std::mem::transmute(equations) };
+        let answers: Vec<f64> = (0..size)
+            .map(|_| rng.random_range(difficulty.solution_range()) as f64)
+            .collect();
+        let equations = (0..size)
+            .map(|_| {
+                let coefficients: Vec<f64> = (0..size)
+                    .map(|_| rng.random_range(difficulty.coefficient_range()) as f64)
+                    .collect();
+                let solution = coefficients.iter().zip(&answers).map(|(a, x)| a * x).sum();
+                Equation::new(coefficients, solution)
+            })
+            .collect();
         Self::new(equations)
     }
-    pub const fn switch_rows(&mut self, a: usize, b: usize) {
-        let row_a = self.equations[a];
-        let row_b = self.equations[b];
-        self.equations[b] = row_a;
-        self.equations[a] = row_b;
+}
+impl<F: Field> System<F> {
+    pub fn switch_rows(&mut self, a: usize, b: usize) {
+        self.equations.swap(a, b);
     }
     ///Checks if the coefficient can be made 1 without doing it. Still returns true if the
     ///coefficient is already 1.
-    pub const fn can_make_coefficient_1(&self, equation: usize, coefficient: usize) -> bool {
+    #[allow(dead_code)]
+    pub fn can_make_coefficient_1(&self, equation: usize, coefficient: usize) -> bool {
         self.equations[equation].can_make_coefficient_1(coefficient)
     }
     ///Like `can_make_coefficient_1` but returns false if it's already 1.
-    #[allow(dead_code)]
-    pub const fn should_make_coefficient_1(&self, equation: usize, coefficient: usize) -> bool {
+    pub fn should_make_coefficient_1(&self, equation: usize, coefficient: usize) -> bool {
         self.equations[equation].should_make_coefficient_1(coefficient)
     }
     pub fn make_coefficient_1(&mut self, equation: usize, coefficient: usize) {
@@ -135,23 +241,23 @@ impl System {
     }
     ///Make sure there won't be a divide by 0 in `make_coefficent_0_with_row`. Doesn't need that
     ///functions's first argument. Still returns true if the coefficient is already 0.
-    pub const fn can_make_coefficient_0_with_row(
+    pub fn can_make_coefficient_0_with_row(
         &self,
         equation: usize,
         coefficient: usize,
         with: usize,
     ) -> bool {
-        equation < SYSTEM_SIZE && self.equations[with].coefficients[coefficient] != 0.0
+        equation < self.size() && !self.equations[with].coefficients[coefficient].is_zero()
     }
     ///Like `can_make_coefficient_0_with_row` but returns false if the coefficient is already 0.
-    pub const fn should_make_coefficient_0_with_row(
+    pub fn should_make_coefficient_0_with_row(
         &self,
         equation: usize,
         coefficient: usize,
         with: usize,
     ) -> bool {
         self.can_make_coefficient_0_with_row(equation, coefficient, with)
-            && self.equations[equation].coefficients[coefficient] != 0.0
+            && !self.equations[equation].coefficients[coefficient].is_zero()
     }
     pub fn make_coefficient_0_with_row(
         &mut self,
@@ -162,75 +268,418 @@ impl System {
         //Guido has no authority here.
         let current_coefficient = self.equations[equation].coefficients[coefficient];
         let with_coefficient = self.equations[with].coefficients[coefficient];
-        let to_subtract = self.equations[with] / with_coefficient * current_coefficient;
+        let to_subtract = self.equations[with].clone() / with_coefficient * current_coefficient;
         self.equations[equation] -= to_subtract;
-        debug_assert_eq!(self.equations[equation].coefficients[coefficient], 0.0);
+        debug_assert!(self.equations[equation].coefficients[coefficient].is_zero());
     }
-    pub fn hint(&self) -> Option<(usize, usize, usize)> {
-        for i in 0..SYSTEM_SIZE {
-            for j in i + 1..SYSTEM_SIZE {
-                if self.should_make_coefficient_0_with_row(j, i, i) {
-                    return Some((j, i, i));
+    ///Checks if the operation can be done and would change something, like the `should_`
+    ///methods. Switching two different rows always can.
+    pub fn should_apply(&self, operation: RowOperation) -> bool {
+        match operation {
+            RowOperation::SwitchRows(a, b) => a != b,
+            RowOperation::MakeCoefficient1(equation, coefficient) => {
+                self.should_make_coefficient_1(equation, coefficient)
+            }
+            RowOperation::MakeCoefficient0WithRow(equation, coefficient, with) => {
+                equation != with
+                    && self.should_make_coefficient_0_with_row(equation, coefficient, with)
+            }
+        }
+    }
+    pub fn apply(&mut self, operation: RowOperation) {
+        match operation {
+            RowOperation::SwitchRows(a, b) => self.switch_rows(a, b),
+            RowOperation::MakeCoefficient1(equation, coefficient) => {
+                self.make_coefficient_1(equation, coefficient)
+            }
+            RowOperation::MakeCoefficient0WithRow(equation, coefficient, with) => {
+                self.make_coefficient_0_with_row(equation, coefficient, with)
+            }
+        }
+    }
+    ///Returns the value of each unknown if the coefficients are the identity matrix, meaning the
+    ///system has been solved.
+    pub fn solution(&self) -> Option<Vec<F>> {
+        for (i, equation) in self.equations.iter().enumerate() {
+            for (j, coefficient) in equation.coefficients.iter().enumerate() {
+                let identity = if i == j {
+                    coefficient.is_one()
+                } else {
+                    coefficient.is_zero()
+                };
+                if !identity {
+                    return None;
                 }
             }
         }
-        for i in 0..SYSTEM_SIZE {
-            for j in 0..i {
-                if self.should_make_coefficient_0_with_row(j, i, i) {
-                    return Some((j, i, i));
+        Some(
+            self.equations
+                .iter()
+                .map(|equation| equation.solution)
+                .collect(),
+        )
+    }
+    ///Checks for an equation with all 0 coefficients but a solution that isn't 0, which means the
+    ///system has no solution at all.
+    pub fn is_inconsistent(&self) -> bool {
+        self.equations.iter().any(|equation| {
+            !equation.solution.is_zero()
+                && equation
+                    .coefficients
+                    .iter()
+                    .all(|coefficient| coefficient.is_zero())
+        })
+    }
+    ///Checks if the coefficients are in reduced row echelon form: the first nonzero coefficient
+    ///of each row is 1, is further right than the row above's, and is the only nonzero coefficient
+    ///in its column, and rows of all 0s are at the bottom.
+    pub fn is_reduced_row_echelon_form(&self) -> bool {
+        let mut last_pivot: Option<usize> = None;
+        let mut zero_row_seen = false;
+        for equation in self.equations.iter() {
+            let Some(pivot) = equation
+                .coefficients
+                .iter()
+                .position(|coefficient| !coefficient.is_zero())
+            else {
+                zero_row_seen = true;
+                continue;
+            };
+            if zero_row_seen
+                || !equation.coefficients[pivot].is_one()
+                || last_pivot.is_some_and(|last_pivot| pivot <= last_pivot)
+                || self
+                    .equations
+                    .iter()
+                    .filter(|other| !other.coefficients[pivot].is_zero())
+                    .count()
+                    != 1
+            {
+                return false;
+            }
+            last_pivot = Some(pivot);
+        }
+        true
+    }
+    ///Finds the pivots the way the hints do, going column by column and taking the first row
+    ///with a nonzero coefficient that isn't already another column's pivot, whether or not it has
+    ///been switched into place yet.
+    pub fn structure(&self) -> Structure {
+        let size = self.size();
+        let mut pivot_columns = vec![None; size];
+        let mut cells = vec![vec![CellStatus::Free; size]; size];
+        for column in 0..size {
+            let Some(pivot_row) = (0..size).find(|&i| {
+                pivot_columns[i].is_none() && !self.equations[i].coefficients[column].is_zero()
+            }) else {
+                continue;
+            };
+            pivot_columns[pivot_row] = Some(column);
+            for (i, row) in cells.iter_mut().enumerate() {
+                row[column] = if i == pivot_row {
+                    CellStatus::Pivot
+                } else if self.equations[i].coefficients[column].is_zero() {
+                    CellStatus::Cleared
+                } else {
+                    CellStatus::ToEliminate
+                };
+            }
+        }
+        Structure {
+            pivot_columns,
+            cells,
+        }
+    }
+    ///Suggests the next row operation toward reduced row echelon form. Goes column by column,
+    ///switching a row with a nonzero coefficient into place, making it 1, and then making the rest
+    ///of the column 0. Only looks at the system as it is, so following the hints after doing
+    ///something else still works.
+    pub fn hint(&self) -> Option<RowOperation> {
+        self.hint_with_column().map(|(operation, _)| operation)
+    }
+    ///Like `hint` but also returns the column whose pivot the operation is working on.
+    pub(crate) fn hint_with_column(&self) -> Option<(RowOperation, usize)> {
+        let mut row = 0;
+        for column in 0..self.size() {
+            if row >= self.size() {
+                break;
+            }
+            let Some(pivot_row) =
+                (row..self.size()).find(|&i| !self.equations[i].coefficients[column].is_zero())
+            else {
+                continue;
+            };
+            if pivot_row != row {
+                return Some((RowOperation::SwitchRows(row, pivot_row), column));
+            }
+            if self.should_make_coefficient_1(row, column) {
+                return Some((RowOperation::MakeCoefficient1(row, column), column));
+            }
+            for i in 0..self.size() {
+                if i != row && self.should_make_coefficient_0_with_row(i, column, row) {
+                    return Some((
+                        RowOperation::MakeCoefficient0WithRow(i, column, row),
+                        column,
+                    ));
                 }
             }
+            row += 1;
         }
         None
     }
 }
-impl Neg for System {
+impl<F: Field> Neg for System<F> {
     type Output = Self;
     fn neg(self) -> Self {
-        let mut new_equations = self.equations;
-        for i in 0..SYSTEM_SIZE {
-            new_equations[i] = -new_equations[i];
-        }
-        Self::new(new_equations)
+        Self::new(
+            self.equations
+                .into_iter()
+                .map(|equation| -equation)
+                .collect(),
+        )
     }
 }
-impl Add for System {
+impl<F: Field> Add for System<F> {
     type Output = Self;
     fn add(self, rhs: Self) -> Self {
         let mut new_equations = self.equations;
-        for i in 0..SYSTEM_SIZE {
-            new_equations[i] += rhs.equations[i];
+        for (new_equation, rhs_equation) in new_equations.iter_mut().zip(rhs.equations) {
+            *new_equation += rhs_equation;
         }
         Self::new(new_equations)
     }
 }
-impl_assign!(System, AddAssign, add_assign, Self, +);
-impl Sub for System {
+impl_assign!(System<F>, AddAssign, add_assign, Self, +);
+impl<F: Field> Sub for System<F> {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self {
         self + -rhs
     }
 }
-impl_assign!(System, SubAssign, sub_assign, Self, -);
-impl Mul<f64> for System {
+impl_assign!(System<F>, SubAssign, sub_assign, Self, -);
+impl<F: Field> Mul<F> for System<F> {
     type Output = Self;
-    fn mul(self, rhs: f64) -> Self {
+    fn mul(self, rhs: F) -> Self {
         let mut new_equations = self.equations;
-        for i in 0..SYSTEM_SIZE {
-            new_equations[i] *= rhs;
+        for equation in new_equations.iter_mut() {
+            *equation *= rhs;
         }
         Self::new(new_equations)
     }
 }
-impl_assign!(System, MulAssign, mul_assign, f64, *);
-impl Div<f64> for System {
+impl_assign!(System<F>, MulAssign, mul_assign, F, *);
+impl<F: Field> Div<F> for System<F> {
     type Output = Self;
-    fn div(self, rhs: f64) -> Self {
+    fn div(self, rhs: F) -> Self {
         let mut new_equations = self.equations;
-        for i in 0..SYSTEM_SIZE {
-            new_equations[i] /= rhs;
+        for equation in new_equations.iter_mut() {
+            *equation /= rhs;
         }
         Self::new(new_equations)
     }
 }
-impl_assign!(System, DivAssign, div_assign, f64, /);
+impl_assign!(System<F>, DivAssign, div_assign, F, /);
+///A square matrix, for the elementary matrices that row operations are the same as multiplying by
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Matrix {
+    pub rows: Vec<Vec<f64>>,
+}
+impl Matrix {
+    pub const fn new(rows: Vec<Vec<f64>>) -> Self {
+        Self { rows }
+    }
+    pub fn identity(size: usize) -> Self {
+        Self::new(
+            (0..size)
+                .map(|i| (0..size).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+                .collect(),
+        )
+    }
+    pub fn size(&self) -> usize {
+        self.rows.len()
+    }
+}
+impl Mul for Matrix {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let size = self.size();
+        Self::new(
+            (0..size)
+                .map(|i| {
+                    (0..size)
+                        .map(|j| (0..size).map(|k| self.rows[i][k] * rhs.rows[k][j]).sum())
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+}
+impl_assign!(Matrix, MulAssign, mul_assign, Self, *);
+///Multiplies the augmented matrix of the system, so each equation of the result is a combination of
+///the system's equations.
+impl Mul<System> for Matrix {
+    type Output = System;
+    fn mul(self, rhs: System) -> System {
+        let size = rhs.size();
+        System::new(
+            self.rows
+                .iter()
+                .map(|row| {
+                    let mut equation = Equation::new(vec![0.0; size], 0.0);
+                    for (factor, rhs_equation) in row.iter().zip(rhs.equations.iter()) {
+                        equation += rhs_equation.clone() * *factor;
+                    }
+                    equation
+                })
+                .collect(),
+        )
+    }
+}
+impl System {
+    ///The coefficients without the solutions
+    pub fn coefficient_matrix(&self) -> Matrix {
+        Matrix::new(
+            self.equations
+                .iter()
+                .map(|equation| equation.coefficients.clone())
+                .collect(),
+        )
+    }
+}
+impl RowOperation {
+    ///The matrix that doing the operation to `system` is the same as multiplying on the left by
+    pub fn elementary_matrix(self, system: &System) -> Matrix {
+        let mut matrix = Matrix::identity(system.size());
+        match self {
+            Self::SwitchRows(a, b) => matrix.rows.swap(a, b),
+            Self::MakeCoefficient1(equation, coefficient) => {
+                matrix.rows[equation][equation] =
+                    1.0 / system.equations[equation].coefficients[coefficient];
+            }
+            Self::MakeCoefficient0WithRow(equation, coefficient, with) => {
+                matrix.rows[equation][with] = -system.equations[equation].coefficients[coefficient]
+                    / system.equations[with].coefficients[coefficient];
+            }
+        }
+        matrix
+    }
+}
+impl Matrix {
+    ///The matrix without a row and a column
+    pub fn minor(&self, row: usize, column: usize) -> Self {
+        Self::new(
+            self.rows
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != row)
+                .map(|(_, numbers)| {
+                    numbers
+                        .iter()
+                        .enumerate()
+                        .filter(|(j, _)| *j != column)
+                        .map(|(_, number)| *number)
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+    ///Finds the determinant by cofactor expansion along the first row, adding the arithmetic it
+    ///takes to `count`. That grows like the factorial of the size, so it's slow for big matrices.
+    pub fn determinant_counting(&self, count: &mut OperationCount) -> f64 {
+        match self.size() {
+            0 => 1.0,
+            1 => self.rows[0][0],
+            size => {
+                let mut determinant = 0.0;
+                for column in 0..size {
+                    let cofactor = self.minor(0, column).determinant_counting(count);
+                    let term = self.rows[0][column] * cofactor;
+                    determinant += if column % 2 == 0 { term } else { -term };
+                }
+                count.multiplications += size as u64;
+                count.additions += size as u64 - 1;
+                determinant
+            }
+        }
+    }
+}
+impl System {
+    ///The determinant of the coefficients, which isn't 0 exactly when there's one answer. The
+    ///arithmetic it takes is added to `count`.
+    pub fn determinant(&self, count: &mut OperationCount) -> f64 {
+        self.coefficient_matrix().determinant_counting(count)
+    }
+    ///The coefficients with one column replaced by the solutions, for Cramer's rule
+    pub fn replace_column(&self, column: usize) -> Matrix {
+        let mut matrix = self.coefficient_matrix();
+        for (row, equation) in matrix.rows.iter_mut().zip(self.equations.iter()) {
+            row[column] = equation.solution;
+        }
+        matrix
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use CellStatus::*;
+    fn system(rows: &[&[f64]]) -> System {
+        System::new(
+            rows.iter()
+                .map(|row| Equation::new(row.to_vec(), 0.0))
+                .collect(),
+        )
+    }
+    #[test]
+    fn finds_the_structure_partway_through() {
+        let structure = system(&[&[1.0, 2.0, 0.0], &[0.0, 3.0, 1.0], &[2.0, 0.0, 5.0]]).structure();
+        assert_eq!(structure.pivot_columns, [Some(0), Some(1), Some(2)]);
+        assert_eq!(
+            structure.cells,
+            [
+                [Pivot, ToEliminate, Cleared],
+                [Cleared, Pivot, ToEliminate],
+                [ToEliminate, Cleared, Pivot],
+            ]
+        );
+        //A pivot can be found before it's switched into place.
+        let structure = system(&[&[0.0, 1.0], &[1.0, 0.0]]).structure();
+        assert_eq!(structure.pivot_columns, [Some(1), Some(0)]);
+        assert_eq!(structure.cells, [[Cleared, Pivot], [Pivot, Cleared]]);
+    }
+    #[test]
+    fn leaves_columns_of_0s_free() {
+        let structure = system(&[&[0.0, 1.0], &[0.0, 2.0]]).structure();
+        assert_eq!(structure.pivot_columns, [Some(1), None]);
+        assert_eq!(structure.cells, [[Free, Pivot], [Free, ToEliminate]]);
+    }
+    #[test]
+    fn finds_the_structure_of_rref() {
+        let structure = System::new(
+            (0..3)
+                .map(|i| Equation::new((0..3).map(|j| (i == j) as u8 as f64).collect(), 1.0))
+                .collect(),
+        )
+        .structure();
+        assert_eq!(structure.pivot_columns, [Some(0), Some(1), Some(2)]);
+        assert_eq!(
+            structure.cells,
+            [
+                [Pivot, Cleared, Cleared],
+                [Cleared, Pivot, Cleared],
+                [Cleared, Cleared, Pivot],
+            ]
+        );
+        let reduced = system(&[&[1.0, 2.0, 0.0], &[0.0, 0.0, 1.0], &[0.0, 0.0, 0.0]]);
+        assert!(reduced.is_reduced_row_echelon_form());
+        let structure = reduced.structure();
+        assert_eq!(structure.pivot_columns, [Some(0), Some(2), None]);
+        assert_eq!(
+            structure.cells,
+            [
+                [Pivot, Free, Cleared],
+                [Cleared, Free, Pivot],
+                [Cleared, Free, Cleared],
+            ]
+        );
+    }
+}
@@ -0,0 +1,46 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!Complex number mode. Systems are made from Gaussian integers, complex numbers whose real and
+//!imaginary parts are both whole, with a Gaussian integer answer, so the answer is easy to check.
+use super::*;
+use rand::Rng;
+fn random_gaussian_integer(rng: &mut impl Rng) -> Complex {
+    Complex::new(
+        rng.random_range(-3..=3) as f64,
+        rng.random_range(-3..=3) as f64,
+    )
+}
+impl System<Complex> {
+    pub fn random_complex(size: usize, rng: &mut impl Rng) -> Self {
+        let answers: Vec<Complex> = (0..size).map(|_| random_gaussian_integer(rng)).collect();
+        let equations = (0..size)
+            .map(|_| {
+                let coefficients: Vec<Complex> =
+                    (0..size).map(|_| random_gaussian_integer(rng)).collect();
+                let solution = coefficients
+                    .iter()
+                    .zip(&answers)
+                    .fold(Complex::default(), |total, (a, x)| total + *a * *x);
+                Equation::new(coefficients, solution)
+            })
+            .collect();
+        Self::new(equations)
+    }
+}
+impl Game {
+    ///Puts a system of Gaussian integers with a unique solution on the board.
+    pub fn start_complex(&self) {
+        let system = loop {
+            let system = System::random_complex(self.size.get(), &mut *self.rng.borrow_mut());
+            if system.has_unique_solution() {
+                break system;
+            }
+        };
+        let system = FieldSystem::Complex(system);
+        self.set_field_system(system);
+        self.hud.set_label(tr(
+            "The coefficients are complex numbers a+bi, where i² = -1. Dividing by a+bi is multiplying by a-bi and dividing by a²+b².",
+        ));
+        self.hud.set_visible(true);
+    }
+}
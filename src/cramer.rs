@@ -0,0 +1,298 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!Cramer's rule mode. Once the player has solved the system, it's solved again by Cramer's rule
+//!and by elimination with back substitution, and the arithmetic each one took is compared.
+//!Arithmetic is counted the way it's done by hand, skipping numbers that are 0 and the one the
+//!operation is for, since what it becomes is already known: scaling a row divides the other
+//!numbers in it, and adding a multiple of a row takes one division for the multiple and then a
+//!multiplication and a subtraction for each of the other numbers in that row.
+use super::*;
+///How much arithmetic something took
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OperationCount {
+    ///Including subtractions
+    pub additions: u64,
+    ///Including divisions
+    pub multiplications: u64,
+}
+impl OperationCount {
+    pub const fn total(self) -> u64 {
+        self.additions + self.multiplications
+    }
+}
+impl Add for OperationCount {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            additions: self.additions + rhs.additions,
+            multiplications: self.multiplications + rhs.multiplications,
+        }
+    }
+}
+impl AddAssign for OperationCount {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+impl RowOperation {
+    ///The arithmetic the operation takes on `system`, which is from before the operation
+    pub fn operation_count(self, system: &System) -> OperationCount {
+        //The numbers in a row that aren't 0, apart from the one in `column`
+        let nonzero = |row: usize, column: usize| {
+            let equation = &system.equations[row];
+            let coefficients = equation
+                .coefficients
+                .iter()
+                .enumerate()
+                .filter(|(i, coefficient)| *i != column && **coefficient != 0.0)
+                .count();
+            coefficients as u64 + u64::from(equation.solution != 0.0)
+        };
+        match self {
+            Self::SwitchRows(..) => OperationCount::default(),
+            Self::MakeCoefficient1(equation, coefficient) => OperationCount {
+                additions: 0,
+                multiplications: nonzero(equation, coefficient),
+            },
+            Self::MakeCoefficient0WithRow(_, coefficient, with) => {
+                let numbers = nonzero(with, coefficient);
+                OperationCount {
+                    additions: numbers,
+                    multiplications: numbers + 1,
+                }
+            }
+        }
+    }
+}
+///The arithmetic elimination takes to make a system of `size` equations upper triangular, skipping
+///the coefficients that are already known to be 0, and then solve it from the bottom up. Switching
+///rows doesn't take any, so it's the same for every system that size.
+pub fn back_substitution_count(size: usize) -> OperationCount {
+    let mut count = OperationCount::default();
+    for pivot in 0..size {
+        //Every number right of the pivot, including the solution
+        let right = (size - pivot) as u64;
+        let below = (size - pivot - 1) as u64;
+        count.multiplications += below * (1 + right);
+        count.additions += below * right;
+    }
+    for row in 0..size {
+        //The unknowns already found are multiplied by their coefficients and subtracted before
+        //dividing by the pivot.
+        let known = (size - row - 1) as u64;
+        count.multiplications += known + 1;
+        count.additions += known;
+    }
+    count
+}
+impl System {
+    ///Solves the system by Cramer's rule, with determinants found by cofactor expansion. Also
+    ///returns each determinant, with the coefficients' first, and the arithmetic it all took.
+    pub fn cramer(&self) -> (Option<Vec<f64>>, Vec<f64>, OperationCount) {
+        let mut count = OperationCount::default();
+        let determinant = self.determinant(&mut count);
+        let mut determinants = vec![determinant];
+        for column in 0..self.size() {
+            determinants.push(self.replace_column(column).determinant_counting(&mut count));
+        }
+        if determinant == 0.0 {
+            return (None, determinants, count);
+        }
+        count.multiplications += self.size() as u64;
+        let answers = determinants[1..]
+            .iter()
+            .map(|replaced| replaced / determinant)
+            .collect();
+        (Some(answers), determinants, count)
+    }
+}
+fn describe_count(count: OperationCount) -> String {
+    tr_format(
+        "{} operations ({} multiplications and divisions, {} additions and subtractions)",
+        &[&count.total(), &count.multiplications, &count.additions],
+    )
+}
+impl Game {
+    ///Puts a system with a unique solution on the board to be compared with Cramer's rule once
+    ///it's solved.
+    pub fn start_cramer(&self) {
+        let system = loop {
+            let system = System::random(
+                self.size.get(),
+                self.difficulty.get(),
+                &mut *self.rng.borrow_mut(),
+            );
+            if system.has_unique_solution() {
+                break system;
+            }
+        };
+        self.set_system(system, None);
+        self.compare_cramer.set(true);
+        self.hud.set_label(tr("Solve the system by elimination. Then it's solved again by Cramer's rule to compare how much arithmetic each way takes."));
+        self.hud.set_visible(true);
+    }
+    ///Shows how solving the system compares to Cramer's rule if it's just been solved in Cramer's
+    ///rule mode.
+    pub(crate) fn compare_with_cramer(&self) {
+        if !self.compare_cramer.take() {
+            return;
+        }
+        let start_system = self.start_system.borrow().clone();
+        let size = start_system.size();
+        let number_format = self.number_format.get();
+        let operations = self.operations.borrow();
+        let mut system = start_system.clone();
+        let mut player_count = OperationCount::default();
+        for operation in operations.iter() {
+            player_count += operation.operation_count(&system);
+            system.apply(*operation);
+        }
+        let (answers, determinants, cramer_count) = start_system.cramer();
+        let mut text = tr_format(
+            if self.assisted.get() {
+                "The {} row operations took {}."
+            } else {
+                "Your {} row operations took {}."
+            },
+            &[&operations.len(), &describe_count(player_count)],
+        );
+        text.push_str(&tr_format(
+            "\n\nCramer's rule took {} to find {} determinants by cofactor expansion and divide by det(A) = {}:",
+            &[
+                &describe_count(cramer_count),
+                &(size + 1),
+                &format_number(determinants[0], number_format),
+            ],
+        ));
+        for (i, replaced) in determinants[1..].iter().enumerate() {
+            text.push_str(&format!(
+                "\nx{} = det(A{}) / det(A) = {} / {}",
+                i + 1,
+                i + 1,
+                format_number(*replaced, number_format),
+                format_number(determinants[0], number_format)
+            ));
+            if let Some(answers) = answers.as_ref() {
+                text.push_str(&format!(" = {}", format_number(answers[i], number_format)));
+            }
+        }
+        text.push_str(&tr_format(
+            "\n\nElimination with back substitution takes {}.",
+            &[&describe_count(back_substitution_count(size))],
+        ));
+        self.hud.set_visible(false);
+        let dialog = MessageDialog::builder()
+            .message_type(MessageType::Info)
+            .buttons(ButtonsType::Ok)
+            .text(tr("Compared with Cramer's Rule"))
+            .secondary_text(text)
+            .modal(true)
+            .build();
+        dialog.set_transient_for(self.drawing_area.root().and_downcast_ref::<gtk4::Window>());
+        dialog.connect_response(|dialog, _| dialog.destroy());
+        dialog.present();
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn system() -> System {
+        System::new(vec![
+            Equation::new(vec![2.0, 1.0], 3.0),
+            Equation::new(vec![4.0, 5.0], 6.0),
+        ])
+    }
+    #[test]
+    fn counts_elimination_like_back_substitution() {
+        //Following the hints on a system with no 0s is elimination with back substitution done
+        //in a different order, with the same divisions and one fewer subtraction since the last
+        //solution becomes 0.
+        let mut system = system();
+        let mut count = OperationCount::default();
+        while let Some(hint) = system.hint() {
+            count += hint.operation_count(&system);
+            system.apply(hint);
+        }
+        assert_eq!(
+            count,
+            OperationCount {
+                additions: 2,
+                multiplications: 6
+            }
+        );
+        assert_eq!(
+            back_substitution_count(2),
+            OperationCount {
+                additions: 3,
+                multiplications: 6
+            }
+        );
+    }
+    #[test]
+    fn skips_zeros() {
+        let system = System::new(vec![
+            Equation::new(vec![1.0, 0.0, 2.0], 0.0),
+            Equation::new(vec![3.0, 1.0, 1.0], 1.0),
+            Equation::new(vec![0.0, 0.0, 4.0], 8.0),
+        ]);
+        assert_eq!(
+            RowOperation::MakeCoefficient0WithRow(1, 0, 0).operation_count(&system),
+            OperationCount {
+                additions: 1,
+                multiplications: 2
+            }
+        );
+        assert_eq!(
+            RowOperation::MakeCoefficient1(2, 2).operation_count(&system),
+            OperationCount {
+                additions: 0,
+                multiplications: 1
+            }
+        );
+        assert_eq!(
+            RowOperation::SwitchRows(0, 2).operation_count(&system),
+            OperationCount::default()
+        );
+    }
+    #[test]
+    fn finds_determinants() {
+        let mut count = OperationCount::default();
+        assert_eq!(system().determinant(&mut count), 6.0);
+        assert_eq!(
+            count,
+            OperationCount {
+                additions: 1,
+                multiplications: 2
+            }
+        );
+        let matrix = Matrix::new(vec![
+            vec![2.0, 0.0, 1.0],
+            vec![1.0, 3.0, 2.0],
+            vec![1.0, 1.0, 1.0],
+        ]);
+        let mut count = OperationCount::default();
+        assert_eq!(matrix.determinant_counting(&mut count), 0.0);
+        assert_eq!(
+            count,
+            OperationCount {
+                additions: 5,
+                multiplications: 9
+            }
+        );
+        assert_eq!(
+            Matrix::identity(4).determinant_counting(&mut OperationCount::default()),
+            1.0
+        );
+    }
+    #[test]
+    fn solves_by_cramers_rule() {
+        let (answers, determinants, _) = system().cramer();
+        assert_eq!(determinants, [6.0, 9.0, -0.0]);
+        assert_eq!(answers, Some(vec![1.5, 0.0]));
+        let singular = System::new(vec![
+            Equation::new(vec![1.0, 2.0], 3.0),
+            Equation::new(vec![2.0, 4.0], 6.0),
+        ]);
+        assert_eq!(singular.cramer().0, None);
+    }
+}
@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!The daily puzzle, a new system every day that's the same for everyone playing with the same
+//!size and difficulty, and the streak of days in a row it's been solved.
+use super::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+///The number of days since the Unix epoch in UTC, so everyone gets a new daily puzzle at the same
+///time.
+fn today() -> i64 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    (seconds / (24 * 60 * 60)) as i64
+}
+///Makes the system for `day`.
+fn daily_system(day: i64, size: usize, difficulty: Difficulty) -> System {
+    let mut rng = StdRng::seed_from_u64(day as u64);
+    loop {
+        let system = System::random(size, difficulty, &mut rng);
+        if system.has_unique_solution() {
+            return system;
+        }
+    }
+}
+impl Stats {
+    ///Keeps the streak going if the daily puzzle was also solved yesterday. Solving it again the
+    ///same day doesn't count twice.
+    pub fn record_daily(&mut self, day: i64) {
+        match self.last_daily {
+            Some(last) if last == day => return,
+            Some(last) if last == day - 1 => self.daily_streak += 1,
+            _ => self.daily_streak = 1,
+        }
+        self.last_daily = Some(day);
+        self.best_daily_streak = self.best_daily_streak.max(self.daily_streak);
+    }
+    ///The streak as of today, which is broken if neither today's nor yesterday's puzzle was solved
+    pub fn current_daily_streak(&self) -> u64 {
+        match self.last_daily {
+            Some(last) if last >= today() - 1 => self.daily_streak,
+            _ => 0,
+        }
+    }
+}
+impl Game {
+    ///Puts today's puzzle on the board with the streak above it.
+    pub(crate) fn start_daily(&self) {
+        let day = today();
+        self.set_system(
+            daily_system(day, self.size.get(), self.difficulty.get()),
+            None,
+        );
+        self.daily.set(Some(day));
+        self.hud.set_label(&tr_format(
+            "Daily puzzle   Streak {}",
+            &[&self.stats.borrow().current_daily_streak()],
+        ));
+        self.hud.set_visible(true);
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn makes_the_same_system_all_day() {
+        let system = daily_system(20000, 3, Difficulty::Medium);
+        assert_eq!(system, daily_system(20000, 3, Difficulty::Medium));
+        assert_ne!(system, daily_system(20001, 3, Difficulty::Medium));
+        assert!(system.has_unique_solution());
+    }
+    #[test]
+    fn keeps_streaks() {
+        let mut stats = Stats::default();
+        stats.record_daily(10);
+        stats.record_daily(11);
+        stats.record_daily(11);
+        assert_eq!((stats.daily_streak, stats.best_daily_streak), (2, 2));
+        stats.record_daily(13);
+        assert_eq!((stats.daily_streak, stats.best_daily_streak), (1, 2));
+        assert_eq!(stats.current_daily_streak(), 0);
+        stats.record_daily(today());
+        assert_eq!(stats.current_daily_streak(), 1);
+    }
+}
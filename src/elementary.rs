@@ -0,0 +1,145 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!The panel next to the board that shows the elementary matrix for each row operation. Doing an
+//!operation to the system is the same as multiplying it on the left by the operation's elementary
+//!matrix, so after operations E₁ through Eₖ the coefficients are Eₖ⋯E₁A.
+use super::*;
+const CELL_WIDTH: f64 = 60.0;
+const ROW_HEIGHT: f64 = 22.0;
+const TITLE_HEIGHT: f64 = 26.0;
+const MARGIN: f64 = 10.0;
+///Writes a number with subscript digits.
+fn subscript(number: usize) -> String {
+    number
+        .to_string()
+        .chars()
+        .map(|digit| match digit {
+            '0' => '₀',
+            '1' => '₁',
+            '2' => '₂',
+            '3' => '₃',
+            '4' => '₄',
+            '5' => '₅',
+            '6' => '₆',
+            '7' => '₇',
+            '8' => '₈',
+            '9' => '₉',
+            _ => digit,
+        })
+        .collect()
+}
+///How much room a matrix of `size` rows takes up with its title above it and a margin below it
+pub fn titled_matrix_size(size: usize) -> (f64, f64) {
+    (
+        CELL_WIDTH * size as f64 + MARGIN,
+        TITLE_HEIGHT + ROW_HEIGHT * size as f64 + MARGIN,
+    )
+}
+///How big the panel needs to be for a system of `size` equations
+pub fn matrix_panel_size(size: usize) -> (i32, i32) {
+    let (width, height) = titled_matrix_size(size);
+    //Wide enough for the titles even when the matrices are small
+    (
+        (width + MARGIN).max(220.0) as i32,
+        (3.0 * height + MARGIN) as i32,
+    )
+}
+pub fn build_matrix_panel(size: usize) -> DrawingArea {
+    let (width, height) = matrix_panel_size(size);
+    DrawingArea::builder()
+        .width_request(width)
+        .height_request(height)
+        .margin_top(10)
+        .margin_bottom(10)
+        .margin_end(10)
+        .visible(false)
+        .build()
+}
+///Draws a matrix with square brackets and a title above it with its top left corner at `(x, y)`
+///and returns where the next thing below it goes.
+pub fn draw_titled_matrix(
+    context: &gtk4::cairo::Context,
+    x: f64,
+    y: f64,
+    title: &str,
+    matrix: &Matrix,
+    number_format: NumberFormat,
+) -> f64 {
+    context.set_font_size(14.0);
+    context.move_to(x, y + TITLE_HEIGHT - 8.0);
+    context.show_text(title).unwrap();
+    let y = y + TITLE_HEIGHT;
+    let size = matrix.size();
+    let height = ROW_HEIGHT * size as f64;
+    let right = x + CELL_WIDTH * size as f64;
+    context.move_to(x + 5.0, y);
+    context.line_to(x, y);
+    context.line_to(x, y + height);
+    context.line_to(x + 5.0, y + height);
+    context.stroke().unwrap();
+    context.move_to(right - 5.0, y);
+    context.line_to(right, y);
+    context.line_to(right, y + height);
+    context.line_to(right - 5.0, y + height);
+    context.stroke().unwrap();
+    for (i, row) in matrix.rows.iter().enumerate() {
+        for (j, number) in row.iter().enumerate() {
+            draw_text_centered(
+                context,
+                x + CELL_WIDTH * (j as f64 + 0.5),
+                y + ROW_HEIGHT * (i as f64 + 0.5),
+                &format_number(*number, number_format),
+            );
+        }
+    }
+    y + height + MARGIN
+}
+///Draws the last operation's elementary matrix, the product of all of them so far, and that
+///product times the starting coefficients in the current color.
+pub fn draw_elementary_matrices(
+    context: &gtk4::cairo::Context,
+    start_system: &System,
+    operations: &[RowOperation],
+    number_format: NumberFormat,
+) {
+    let size = start_system.size();
+    let mut system = start_system.clone();
+    let mut product = Matrix::identity(size);
+    let mut last = None;
+    for operation in operations {
+        let elementary = operation.elementary_matrix(&system);
+        product = elementary.clone() * product;
+        last = Some((
+            describe_operation(&system, *operation, number_format),
+            elementary,
+        ));
+        system.apply(*operation);
+    }
+    let k = subscript(operations.len());
+    let result = product.clone() * start_system.clone();
+    //The product is checked against the board instead of itself since multiplying adds rounding
+    //error the row operations didn't.
+    let reduced = if system.is_reduced_row_echelon_form() {
+        " = RREF(A)"
+    } else {
+        ""
+    };
+    let (last_title, last_matrix) = match last {
+        Some((description, elementary)) => (format!("E{}: {}", k, description), elementary),
+        None => (tr("No operations yet").to_string(), Matrix::identity(size)),
+    };
+    let product_title = match operations.len() {
+        0 => "I".to_string(),
+        1 => "E₁".to_string(),
+        _ => format!("E{}⋯E₁", k),
+    };
+    let result_title = format!("{}A{}", product_title, reduced);
+    let mut y = MARGIN;
+    for (title, matrix) in [
+        (last_title, last_matrix),
+        (product_title, product),
+        (result_title, result.coefficient_matrix()),
+    ] {
+        y = draw_titled_matrix(context, MARGIN, y, &title, &matrix, number_format);
+    }
+}
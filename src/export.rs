@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+use super::*;
+use gtk4::cairo::{Context, Format, ImageSurface, PdfSurface, SvgSurface};
+use std::error::Error;
+use std::fs::File;
+use std::path::Path;
+const MARGIN: f64 = 10.0;
+const HISTORY_LINE_HEIGHT: f64 = 25.0;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+    Pdf,
+}
+impl ImageFormat {
+    ///Picks the format from the file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "svg" => Some(Self::Svg),
+            "png" => Some(Self::Png),
+            "pdf" => Some(Self::Pdf),
+            _ => None,
+        }
+    }
+}
+///What to draw in an exported image besides the board itself.
+#[derive(Clone, Copy, Debug)]
+pub struct ExportOptions<'a> {
+    pub scale: f64,
+    pub hint: Option<RowOperation>,
+    ///The system before any of the operations and the operations done to it since
+    pub history: Option<(&'a System, &'a [RowOperation])>,
+    pub number_format: NumberFormat,
+}
+fn draw_history(
+    context: &Context,
+    start: &System,
+    operations: &[RowOperation],
+    number_format: NumberFormat,
+) {
+    context.set_font_size(18.0);
+    let mut system = start.clone();
+    for (i, operation) in operations.iter().enumerate() {
+        context.move_to(
+            0.0,
+            BOX_SIZE * system.size() as f64 + HISTORY_LINE_HEIGHT * (i + 1) as f64,
+        );
+        context
+            .show_text(&format!(
+                "{}. {}",
+                i + 1,
+                describe_operation(&system, *operation, number_format)
+            ))
+            .unwrap();
+        system.apply(*operation);
+    }
+}
+///Draws the board onto a white background with a margin around it, in unscaled units.
+fn draw_export(
+    context: &Context,
+    system: &System,
+    options: &ExportOptions,
+    width: f64,
+    height: f64,
+) {
+    context.set_source_rgb(1.0, 1.0, 1.0);
+    context.rectangle(0.0, 0.0, width, height);
+    context.fill().unwrap();
+    context.set_source_rgb(0.0, 0.0, 0.0);
+    context.translate(MARGIN, MARGIN);
+    draw_board(
+        context,
+        system,
+        BoardStyle {
+            box_size: BOX_SIZE,
+            number_format: options.number_format,
+            palette: Palette::export(),
+            structure: false,
+        },
+        None,
+        options.hint,
+        &[],
+        &[],
+    );
+    if let Some((start, operations)) = options.history {
+        context.set_source_rgb(0.0, 0.0, 0.0);
+        draw_history(context, start, operations, options.number_format);
+    }
+}
+///Renders the board to a file, choosing the surface type from `format`.
+pub fn export_image(
+    path: &Path,
+    format: ImageFormat,
+    system: &System,
+    options: &ExportOptions,
+) -> Result<(), Box<dyn Error>> {
+    let width = BOX_SIZE * (system.size() + 2) as f64 + MARGIN * 2.0;
+    let history_lines = options
+        .history
+        .map_or(0, |(_, operations)| operations.len());
+    let height =
+        BOX_SIZE * system.size() as f64 + HISTORY_LINE_HEIGHT * history_lines as f64 + MARGIN * 2.0;
+    let scaled_width = width * options.scale;
+    let scaled_height = height * options.scale;
+    match format {
+        ImageFormat::Svg => {
+            let surface = SvgSurface::new(scaled_width, scaled_height, Some(path))?;
+            let context = Context::new(&surface)?;
+            context.scale(options.scale, options.scale);
+            draw_export(&context, system, options, width, height);
+            surface.finish();
+        }
+        ImageFormat::Png => {
+            let surface = ImageSurface::create(
+                Format::ARgb32,
+                scaled_width.ceil() as i32,
+                scaled_height.ceil() as i32,
+            )?;
+            let context = Context::new(&surface)?;
+            context.scale(options.scale, options.scale);
+            draw_export(&context, system, options, width, height);
+            drop(context);
+            surface.write_to_png(&mut File::create(path)?)?;
+        }
+        ImageFormat::Pdf => {
+            let surface = PdfSurface::new(scaled_width, scaled_height, path)?;
+            let context = Context::new(&surface)?;
+            context.scale(options.scale, options.scale);
+            draw_export(&context, system, options, width, height);
+            surface.finish();
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,597 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!The kinds of numbers a system can be made of. Row operations, hints, and checking for a
+//!solution only add, subtract, multiply, and divide, so they work the same for the real numbers
+//!as `f64`, exact fractions, the integers mod a prime, the complex numbers, and fractions of
+//!polynomials in a parameter.
+use super::*;
+use std::fmt::{self, Display, Formatter};
+///Numbers that can be added, subtracted, multiplied, and divided by anything but 0
+pub trait Field:
+    Copy
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn is_zero(self) -> bool;
+    fn is_one(self) -> bool;
+    ///Whether the number is less than 0, so that subtracting a multiple of it reads better as
+    ///adding. Numbers that aren't in order, like complex numbers, never are.
+    fn is_negative(self) -> bool;
+    ///How the number is shown on the board
+    fn format(self, number_format: NumberFormat) -> String;
+}
+impl Field for f64 {
+    fn is_zero(self) -> bool {
+        self == 0.0
+    }
+    fn is_one(self) -> bool {
+        self == 1.0
+    }
+    fn is_negative(self) -> bool {
+        self < 0.0
+    }
+    fn format(self, number_format: NumberFormat) -> String {
+        format_number(self, number_format)
+    }
+}
+///An integer mod a prime. Only numbers with the same modulus can be combined.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Modular {
+    ///Always at least 0 and less than `modulus`
+    value: u32,
+    modulus: u32,
+}
+impl Modular {
+    pub fn new(value: i64, modulus: u32) -> Self {
+        Self {
+            value: value.rem_euclid(modulus as i64) as u32,
+            modulus,
+        }
+    }
+    pub const fn value(self) -> u32 {
+        self.value
+    }
+    pub const fn modulus(self) -> u32 {
+        self.modulus
+    }
+    ///The number that gives 1 when multiplied by this one, found with the extended Euclidean
+    ///algorithm in about as many steps as the modulus has digits. 0 has no inverse.
+    pub fn inverse(self) -> Option<Self> {
+        //Each remainder is its coefficient times this number, mod the modulus.
+        let (mut remainder, mut next_remainder) = (self.modulus as i64, self.value as i64);
+        let (mut coefficient, mut next_coefficient) = (0, 1);
+        while next_remainder != 0 {
+            let quotient = remainder / next_remainder;
+            (remainder, next_remainder) = (next_remainder, remainder - quotient * next_remainder);
+            (coefficient, next_coefficient) =
+                (next_coefficient, coefficient - quotient * next_coefficient);
+        }
+        //The last remainder is the greatest common divisor, which is 1 for anything but 0 since
+        //the modulus is prime.
+        (remainder == 1).then(|| Self::new(coefficient, self.modulus))
+    }
+}
+impl Display for Modular {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.value)
+    }
+}
+impl Field for Modular {
+    fn is_zero(self) -> bool {
+        self.value == 0
+    }
+    fn is_one(self) -> bool {
+        self.value == 1
+    }
+    fn is_negative(self) -> bool {
+        false
+    }
+    fn format(self, _number_format: NumberFormat) -> String {
+        self.to_string()
+    }
+}
+impl Neg for Modular {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-(self.value as i64), self.modulus)
+    }
+}
+impl Add for Modular {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        debug_assert_eq!(self.modulus, rhs.modulus);
+        Self::new(self.value as i64 + rhs.value as i64, self.modulus)
+    }
+}
+impl Sub for Modular {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+impl Mul for Modular {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        debug_assert_eq!(self.modulus, rhs.modulus);
+        Self::new(self.value as i64 * rhs.value as i64, self.modulus)
+    }
+}
+impl Div for Modular {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        Mul::mul(self, rhs.inverse().expect("division by 0"))
+    }
+}
+///The greatest common divisor of the absolute values, which is 0 only if both are 0
+pub fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+///An exact fraction of integers in lowest terms, with the sign on the numerator. Arithmetic
+///panics if a number gets too big for an `i128`, like the integer types do in debug builds, so
+///anything that can keep multiplying, like a player's moves, uses the `checked_` methods.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+pub(crate) const OVERFLOW: &str = "arithmetic overflow";
+impl Rational {
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        Self::checked_new(numerator, denominator).expect(OVERFLOW)
+    }
+    ///Like `new`, but `None` if a number is too big
+    pub fn checked_new(numerator: i128, denominator: i128) -> Option<Self> {
+        assert_ne!(denominator, 0, "division by 0");
+        //`gcd` takes the absolute values, and there's no positive `-i128::MIN`.
+        numerator.checked_abs()?;
+        denominator.checked_abs()?;
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Some(Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        })
+    }
+    pub const fn integer(integer: i128) -> Self {
+        Self {
+            numerator: integer,
+            denominator: 1,
+        }
+    }
+    pub const fn numerator(self) -> i128 {
+        self.numerator
+    }
+    pub const fn denominator(self) -> i128 {
+        self.denominator
+    }
+    pub fn to_f64(self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            numerator: self.numerator.checked_neg()?,
+            denominator: self.denominator,
+        })
+    }
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Self::checked_new(
+            self.numerator
+                .checked_mul(rhs.denominator)?
+                .checked_add(rhs.numerator.checked_mul(self.denominator)?)?,
+            self.denominator.checked_mul(rhs.denominator)?,
+        )
+    }
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(rhs.checked_neg()?)
+    }
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Self::checked_new(
+            self.numerator.checked_mul(rhs.numerator)?,
+            self.denominator.checked_mul(rhs.denominator)?,
+        )
+    }
+    ///`None` if a number is too big or `rhs` is 0
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None;
+        }
+        Self::checked_new(
+            self.numerator.checked_mul(rhs.denominator)?,
+            self.denominator.checked_mul(rhs.numerator)?,
+        )
+    }
+}
+impl Default for Rational {
+    fn default() -> Self {
+        Self::integer(0)
+    }
+}
+impl Display for Rational {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        if self.denominator == 1 {
+            write!(formatter, "{}", self.numerator)
+        } else {
+            write!(formatter, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+impl Field for Rational {
+    fn is_zero(self) -> bool {
+        self.numerator == 0
+    }
+    fn is_one(self) -> bool {
+        self.numerator == 1 && self.denominator == 1
+    }
+    fn is_negative(self) -> bool {
+        self.numerator < 0
+    }
+    fn format(self, number_format: NumberFormat) -> String {
+        match number_format {
+            NumberFormat::Decimal => format_float(self.to_f64()),
+            NumberFormat::Fraction => self.to_string(),
+        }
+    }
+}
+impl Neg for Rational {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.checked_neg().expect(OVERFLOW)
+    }
+}
+impl Add for Rational {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).expect(OVERFLOW)
+    }
+}
+impl Sub for Rational {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self.checked_sub(rhs).expect(OVERFLOW)
+    }
+}
+impl Mul for Rational {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        self.checked_mul(rhs).expect(OVERFLOW)
+    }
+}
+impl Div for Rational {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        assert!(!rhs.is_zero(), "division by 0");
+        self.checked_div(rhs).expect(OVERFLOW)
+    }
+}
+///A complex number `re + im`i
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+impl Complex {
+    pub const fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+}
+impl Field for Complex {
+    fn is_zero(self) -> bool {
+        self.re == 0.0 && self.im == 0.0
+    }
+    fn is_one(self) -> bool {
+        self.re == 1.0 && self.im == 0.0
+    }
+    fn is_negative(self) -> bool {
+        false
+    }
+    fn format(self, number_format: NumberFormat) -> String {
+        format_complex(self, number_format)
+    }
+}
+impl Neg for Complex {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+impl Add for Complex {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+impl Sub for Complex {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+impl Mul for Complex {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+impl Div for Complex {
+    type Output = Self;
+    ///Multiplies both by the conjugate of `rhs` to make the denominator real. Done this way, a
+    ///number divided by itself is exactly 1 even with rounding, which row operations count on.
+    fn div(self, rhs: Self) -> Self {
+        let denominator = rhs.re * rhs.re + rhs.im * rhs.im;
+        Self::new(
+            (self.re * rhs.re + self.im * rhs.im) / denominator,
+            (self.im * rhs.re - self.re * rhs.im) / denominator,
+        )
+    }
+}
+///A system made of something other than real numbers, in the modes that use one
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldSystem {
+    Modular(System<Modular>),
+    Complex(System<Complex>),
+    Parameter(ParameterSystem),
+}
+impl FieldSystem {
+    ///A real system the same size to stand in for this one as `Game::system`. A modular system
+    ///becomes its remainders. A complex system becomes its real parts and a system with a
+    ///parameter becomes all 0s, which are only good for their size, so everything that would use
+    ///the numbers is turned off for them or asks this system instead.
+    pub fn to_real(&self) -> System {
+        match self {
+            Self::Modular(system) => system.to_real(),
+            Self::Complex(system) => System::new(
+                system
+                    .equations
+                    .iter()
+                    .map(|equation| {
+                        Equation::new(
+                            equation.coefficients.iter().map(|x| x.re).collect(),
+                            equation.solution.re,
+                        )
+                    })
+                    .collect(),
+            ),
+            Self::Parameter(system) => {
+                let size = system.system.size();
+                System::new(vec![Equation::new(vec![0.0; size], 0.0); size])
+            }
+        }
+    }
+    pub fn should_apply(&self, operation: RowOperation) -> bool {
+        match self {
+            Self::Modular(system) => system.should_apply(operation),
+            Self::Complex(system) => system.should_apply(operation),
+            Self::Parameter(system) => system.system.should_apply(operation),
+        }
+    }
+    ///Checks that the operation can be done exactly, which only fails when polynomials in a
+    ///parameter get too big.
+    pub fn check(&self, operation: RowOperation) -> Result<(), String> {
+        match self {
+            Self::Modular(_) | Self::Complex(_) => Ok(()),
+            Self::Parameter(system) => system.check(operation),
+        }
+    }
+    pub fn apply(&mut self, operation: RowOperation) {
+        match self {
+            Self::Modular(system) => system.apply(operation),
+            Self::Complex(system) => system.apply(operation),
+            Self::Parameter(system) => system.apply(operation),
+        }
+    }
+    ///Whether the system is solved, which the real system from `to_real` can't always tell
+    pub fn is_solved(&self) -> bool {
+        match self {
+            Self::Modular(system) => system.solution().is_some(),
+            Self::Complex(system) => system.solution().is_some(),
+            Self::Parameter(system) => system.system.solution().is_some(),
+        }
+    }
+    ///Whether any number in the system is a fraction, like `has_fraction` for real numbers.
+    ///Remainders mod a prime never are.
+    pub fn has_fraction(&self) -> bool {
+        fn any<F: Field>(system: &System<F>, is_fraction: impl Fn(F) -> bool) -> bool {
+            system.equations.iter().any(|equation| {
+                equation
+                    .coefficients
+                    .iter()
+                    .chain(std::iter::once(&equation.solution))
+                    .any(|number| is_fraction(*number))
+            })
+        }
+        match self {
+            Self::Modular(_) => false,
+            Self::Complex(system) => any(system, |number| {
+                number.re.fract() != 0.0 || number.im.fract() != 0.0
+            }),
+            Self::Parameter(system) => any(&system.system, RationalFunction::is_fraction),
+        }
+    }
+    pub fn hint(&self) -> Option<RowOperation> {
+        match self {
+            Self::Modular(system) => system.hint(),
+            Self::Complex(system) => system.hint(),
+            Self::Parameter(system) => system.system.hint(),
+        }
+    }
+    pub fn explain_hint(&self, number_format: NumberFormat) -> Option<String> {
+        match self {
+            Self::Modular(system) => system.explain_modular_hint(),
+            Self::Complex(system) => explain_hint(system, number_format),
+            Self::Parameter(system) => system.explain_hint(),
+        }
+    }
+    ///Says what each unknown is once the system is solved.
+    pub fn describe_solution(&self, number_format: NumberFormat) -> Option<String> {
+        fn describe<F: Field>(answers: Vec<F>, number_format: NumberFormat) -> String {
+            let answers: Vec<String> = answers
+                .iter()
+                .enumerate()
+                .map(|(i, answer)| format!("x{} = {}", i + 1, answer.format(number_format)))
+                .collect();
+            answers.join(", ")
+        }
+        match self {
+            Self::Modular(system) => Some(tr_format(
+                "Solved mod {}: {}",
+                &[
+                    &system.equations[0].solution.modulus(),
+                    &describe(system.solution()?, number_format),
+                ],
+            )),
+            Self::Complex(system) => Some(tr_format(
+                "Solved: {}",
+                &[&describe(system.solution()?, number_format)],
+            )),
+            Self::Parameter(system) => system.describe_solution(),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn m(value: i64) -> Modular {
+        Modular::new(value, 7)
+    }
+    #[test]
+    fn does_modular_arithmetic() {
+        assert_eq!(m(-1).value(), 6);
+        assert_eq!(m(15).value(), 1);
+        assert_eq!(m(5) + m(4), m(2));
+        assert_eq!(m(2) - m(5), m(4));
+        assert_eq!(m(3) * m(5), m(1));
+        assert_eq!(-m(3), m(4));
+        assert_eq!(-m(0), m(0));
+        assert_eq!(m(1) / m(3), m(5));
+        assert_eq!(m(6) / m(6), m(1));
+    }
+    #[test]
+    fn finds_inverses() {
+        for value in 1..7 {
+            assert_eq!(m(value) * m(value).inverse().unwrap(), m(1));
+        }
+        assert_eq!(m(0).inverse(), None);
+        assert_eq!(m(7).inverse(), None);
+        assert_eq!(Modular::new(1, 2).inverse(), Some(Modular::new(1, 2)));
+        let big = |value| Modular::new(value, 1_000_000_007);
+        assert_eq!(big(2).inverse(), Some(big(500_000_004)));
+        assert_eq!(big(-1).inverse(), Some(big(-1)));
+        assert_eq!(big(0).inverse(), None);
+    }
+    #[test]
+    #[should_panic(expected = "division by 0")]
+    fn refuses_to_divide_by_0() {
+        let _ = m(3) / m(0);
+    }
+    #[test]
+    fn solves_modular_systems() {
+        //2x + y = 3 and x + 3y = 3 mod 7 have x = 4 and y = 2
+        let mut system = System::new(vec![
+            Equation::new(vec![m(2), m(1)], m(3)),
+            Equation::new(vec![m(1), m(3)], m(3)),
+        ]);
+        assert!(system.has_unique_solution());
+        while let Some(operation) = system.hint() {
+            system.apply(operation);
+        }
+        assert!(system.is_reduced_row_echelon_form());
+        assert_eq!(system.solution(), Some(vec![m(4), m(2)]));
+    }
+    #[test]
+    fn solves_rational_systems() {
+        //Exact fractions stay exact where f64 would round: x = 1/3 and y = 1/7
+        let mut system = System::new(vec![
+            Equation::new(
+                vec![Rational::integer(3), Rational::integer(0)],
+                Rational::integer(1),
+            ),
+            Equation::new(
+                vec![Rational::integer(1), Rational::integer(7)],
+                Rational::new(4, 3),
+            ),
+        ]);
+        while let Some(operation) = system.hint() {
+            system.apply(operation);
+        }
+        assert!(system.is_reduced_row_echelon_form());
+        assert_eq!(
+            system.solution(),
+            Some(vec![Rational::new(1, 3), Rational::new(1, 7)])
+        );
+    }
+    #[test]
+    fn checks_the_actual_system() {
+        //The real parts of [i 0 | 1; 0 1 | 2i] are already solved, but the system isn't.
+        let mut system = FieldSystem::Complex(System::new(vec![
+            Equation::new(
+                vec![Complex::new(0.0, 1.0), Complex::new(0.0, 0.0)],
+                Complex::new(1.0, 0.0),
+            ),
+            Equation::new(
+                vec![Complex::new(0.0, 0.0), Complex::new(1.0, 0.0)],
+                Complex::new(0.0, 2.0),
+            ),
+        ]));
+        assert!(!system.is_solved());
+        assert!(!system.has_fraction());
+        system.apply(system.hint().unwrap());
+        assert!(system.is_solved());
+        assert_eq!(system.hint(), None);
+        let half = FieldSystem::Complex(System::new(vec![Equation::new(
+            vec![Complex::new(1.0, 0.5)],
+            Complex::new(0.0, 0.0),
+        )]));
+        assert!(half.has_fraction());
+        assert!(
+            !FieldSystem::Modular(System::new(vec![Equation::new(vec![m(3)], m(4))]))
+                .has_fraction()
+        );
+    }
+    #[test]
+    fn keeps_fractions_in_lowest_terms() {
+        let half = Rational::new(2, 4);
+        assert_eq!((half.numerator(), half.denominator()), (1, 2));
+        let negative = Rational::new(3, -6);
+        assert_eq!((negative.numerator(), negative.denominator()), (-1, 2));
+        assert_eq!(Rational::new(0, -5), Rational::integer(0));
+        assert_eq!(Rational::new(0, -5).denominator(), 1);
+        let third = Rational::new(1, 3);
+        assert_eq!(half + third, Rational::new(5, 6));
+        assert_eq!(half - third, Rational::new(1, 6));
+        assert_eq!(half * Rational::new(2, 3), third);
+        assert_eq!(half / Rational::new(3, 4), Rational::new(2, 3));
+        assert_eq!(-third, Rational::new(-1, 3));
+        assert!((third / third).is_one());
+        assert_eq!(Rational::new(4, 3).to_string(), "4/3");
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0, 0), 0);
+    }
+    #[test]
+    fn checks_for_overflow() {
+        let big = Rational::integer(i128::MAX);
+        assert_eq!(big.checked_add(Rational::integer(1)), None);
+        assert_eq!(big.checked_mul(Rational::integer(2)), None);
+        assert_eq!(
+            Rational::new(1, i128::MAX).checked_mul(Rational::new(1, 2)),
+            None
+        );
+        assert_eq!(Rational::integer(1).checked_div(Rational::integer(0)), None);
+        assert_eq!(Rational::checked_new(i128::MIN, 1), None);
+        assert_eq!(big.checked_sub(big), Some(Rational::integer(0)));
+    }
+    #[test]
+    #[should_panic(expected = "arithmetic overflow")]
+    fn panics_on_overflow() {
+        let _ = Rational::integer(i128::MAX) + Rational::integer(1);
+    }
+}
@@ -0,0 +1,331 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+use super::*;
+use std::time::Instant;
+///Everything about the system being played that the window's widgets share
+pub struct Game {
+    pub system: RefCell<System>,
+    ///The system before any of `operations`, for exporting
+    pub start_system: RefCell<System>,
+    ///Everything that has been done to the system since it was generated
+    pub operations: RefCell<Vec<RowOperation>>,
+    pub selected_row: Cell<Option<usize>>,
+    pub hint: Cell<Option<RowOperation>>,
+    ///What the last operation changed if it was a mistake, outlined on the board
+    pub mistake_items: RefCell<Vec<CanvasItem>>,
+    ///Whether the board colors the pivots and what's left to eliminate
+    pub show_structure: Cell<bool>,
+    ///The exact answer if the system was made from one, to measure floating point error against
+    pub answers: RefCell<Option<Vec<f64>>>,
+    pub rng: RefCell<StdRng>,
+    ///The size and difficulty of new random systems
+    pub size: Cell<usize>,
+    pub difficulty: Cell<Difficulty>,
+    pub number_format: Cell<NumberFormat>,
+    pub color_scheme: Cell<ColorScheme>,
+    pub drawing_area: DrawingArea,
+    pub mode: Cell<GameMode>,
+    ///Only there while playing a mode other than free play
+    pub run: RefCell<Option<Run>>,
+    pub run_timer: RefCell<Option<glib::SourceId>>,
+    ///Shows the progress of the current run above the board.
+    pub hud: Label,
+    pub stats: RefCell<Stats>,
+    pub puzzle_started: Cell<Instant>,
+    ///Hints asked for on the current system
+    pub puzzle_hints: Cell<u64>,
+    ///Whether the computer did any of the operations on the current system
+    pub assisted: Cell<bool>,
+    ///The day of the daily puzzle if that's the current system
+    pub daily: Cell<Option<i64>>,
+    ///Whether a number that isn't whole has been on the board since the current system started
+    pub fraction_seen: Cell<bool>,
+    ///Shows achievements as they're unlocked.
+    pub toast: gtk4::Revealer,
+    ///Counts toasts so that only the newest one's timer hides the toast.
+    pub toast_generation: Rc<Cell<u32>>,
+    ///The campaign
+    pub levels: Vec<Level>,
+    ///The level being played, if any
+    pub level: Cell<Option<usize>>,
+    ///The step of the tutorial the player is on, if they're doing it
+    pub tutorial_step: Cell<Option<usize>>,
+    ///Explains the hint below the board.
+    pub status: Label,
+    ///Shows the elementary matrices next to the board.
+    pub matrix_panel: DrawingArea,
+    ///L and P so far in LU decomposition mode
+    pub lu: RefCell<Option<LuDecomposition>>,
+    ///Shows L, U, and P next to the board in LU decomposition mode.
+    pub lu_panel: DrawingArea,
+    ///Whether to compare with Cramer's rule once the current system is solved
+    pub compare_cramer: Cell<bool>,
+    ///The actual system in modes with numbers other than real numbers, which `system` stands in
+    ///for
+    pub field_system: RefCell<Option<FieldSystem>>,
+    ///Graphs the equations next to the board.
+    pub geometry_panel: DrawingArea,
+    ///The settings window while it's open, so that clicking "Settings" again brings it back
+    ///instead of opening another
+    pub settings_window: RefCell<Option<gtk4::Window>>,
+}
+impl Game {
+    pub fn new(launch_options: &LaunchOptions, drawing_area: DrawingArea) -> Self {
+        let mut rng = launch_options.rng();
+        let system = launch_options.first_system(&mut rng);
+        Self {
+            system: RefCell::new(system.clone()),
+            start_system: RefCell::new(system.clone()),
+            operations: RefCell::new(Vec::new()),
+            selected_row: Cell::new(None),
+            hint: Cell::new(None),
+            mistake_items: RefCell::new(Vec::new()),
+            show_structure: Cell::new(false),
+            answers: RefCell::new(None),
+            rng: RefCell::new(rng),
+            size: Cell::new(launch_options.size),
+            difficulty: Cell::new(launch_options.difficulty),
+            number_format: Cell::new(launch_options.number_format),
+            color_scheme: Cell::new(launch_options.settings.colors),
+            drawing_area,
+            mode: Cell::new(GameMode::FreePlay),
+            run: RefCell::new(None),
+            run_timer: RefCell::new(None),
+            hud: Label::builder()
+                .wrap(true)
+                .max_width_chars(40)
+                .margin_start(10)
+                .margin_end(10)
+                .visible(false)
+                .build(),
+            stats: RefCell::new(Stats::load()),
+            puzzle_started: Cell::new(Instant::now()),
+            puzzle_hints: Cell::new(0),
+            assisted: Cell::new(false),
+            daily: Cell::new(None),
+            fraction_seen: Cell::new(has_fraction(&system)),
+            toast: build_toast(),
+            toast_generation: Rc::new(Cell::new(0)),
+            levels: campaign(),
+            level: Cell::new(None),
+            tutorial_step: Cell::new(None),
+            status: Label::builder()
+                .wrap(true)
+                .max_width_chars(40)
+                .margin_start(10)
+                .margin_end(10)
+                .margin_bottom(10)
+                .visible(false)
+                .build(),
+            matrix_panel: build_matrix_panel(system.size()),
+            lu: RefCell::new(None),
+            lu_panel: build_lu_panel(system.size()),
+            compare_cramer: Cell::new(false),
+            field_system: RefCell::new(None),
+            geometry_panel: build_geometry_panel(),
+            settings_window: RefCell::new(None),
+        }
+    }
+    ///Does an operation the player asked for, unless the current level, the tutorial, or LU
+    ///decomposition doesn't allow it, and points out if it was a mistake.
+    pub fn player_operation(self: &Rc<Self>, operation: RowOperation) {
+        if let Err(reason) = self
+            .check_level_rules(operation)
+            .and_then(|_| self.check_tutorial(operation))
+            .and_then(|_| self.check_lu(operation))
+            .and_then(|_| self.check_field_system(operation))
+        {
+            self.show_toast(&reason);
+            return;
+        }
+        let mistake = self.find_mistake(operation);
+        self.do_operation(operation);
+        //Before the run can move on to a new system that the outline wouldn't fit
+        if let Some(mistake) = mistake {
+            *self.mistake_items.borrow_mut() = mistake.items(operation);
+            self.report_mistake(mistake);
+        }
+        self.check_level_complete();
+        self.record_move();
+    }
+    ///Does the operation to the system, records it, and redraws. Also counts it toward the
+    ///statistics and the tutorial.
+    pub fn do_operation(self: &Rc<Self>, operation: RowOperation) {
+        let was_solved = self.is_solved();
+        self.record_lu(operation);
+        if !self.apply_field_system(operation) {
+            self.system.borrow_mut().apply(operation);
+        }
+        self.operations.borrow_mut().push(operation);
+        self.mistake_items.borrow_mut().clear();
+        self.clear_hint();
+        self.drawing_area.queue_draw();
+        self.matrix_panel.queue_draw();
+        self.geometry_panel.queue_draw();
+        self.update_lu();
+        self.track_operation();
+        if !was_solved && self.is_solved() {
+            self.record_solve();
+            self.compare_with_cramer();
+        }
+        self.update_level_hud();
+        self.advance_tutorial(operation);
+    }
+    ///Starts over with a different system. `answers` is its exact answer if it's known.
+    pub fn set_system(&self, system: System, answers: Option<Vec<f64>>) {
+        let system_size = system.size();
+        self.fraction_seen.set(has_fraction(&system));
+        self.mistake_items.borrow_mut().clear();
+        *self.start_system.borrow_mut() = system.clone();
+        *self.system.borrow_mut() = system;
+        self.operations.borrow_mut().clear();
+        *self.answers.borrow_mut() = answers;
+        self.clear_hint();
+        self.puzzle_started.set(Instant::now());
+        self.puzzle_hints.set(0);
+        self.assisted.set(false);
+        self.daily.set(None);
+        let was_level = self.level.take().is_some();
+        let was_tutorial = self.tutorial_step.take().is_some();
+        let was_lu = self.lu.take().is_some();
+        let was_cramer = self.compare_cramer.take();
+        let was_field_system = self.field_system.take().is_some();
+        if was_level || was_tutorial || was_lu || was_cramer || was_field_system {
+            self.hud.set_visible(false);
+        }
+        self.lu_panel.set_visible(false);
+        //Levels can be a different size than random systems. The board can grow bigger than this
+        //with the window.
+        self.drawing_area
+            .set_width_request(BOX_SIZE as i32 * (system_size + 2) as i32);
+        self.drawing_area
+            .set_height_request(BOX_SIZE as i32 * system_size as i32);
+        let (panel_width, panel_height) = matrix_panel_size(system_size);
+        self.matrix_panel.set_width_request(panel_width);
+        self.matrix_panel.set_height_request(panel_height);
+        self.drawing_area.queue_draw();
+        self.matrix_panel.queue_draw();
+        self.geometry_panel.queue_draw();
+    }
+    ///Redraws the board and the panels next to it, like after the way they look changes.
+    pub fn redraw_all(&self) {
+        self.drawing_area.queue_draw();
+        self.matrix_panel.queue_draw();
+        self.lu_panel.queue_draw();
+        self.geometry_panel.queue_draw();
+    }
+    ///How big each box on the board is at the board's current size
+    pub fn box_size(&self) -> f64 {
+        box_size(
+            self.drawing_area.width() as f64,
+            self.drawing_area.height() as f64,
+            self.system.borrow().size(),
+        )
+    }
+    ///Finds what's at a point on the board.
+    pub fn item_at(&self, x: f64, y: f64) -> Option<CanvasItem> {
+        CanvasItem::from_coordinates(x, y, self.system.borrow().size(), self.box_size())
+    }
+    ///Shows the hint on the board with an explanation below it and in the board's tooltip.
+    pub fn show_hint(&self) {
+        let system = self.system.borrow().clone();
+        let (hint, explanation, done) = match self.lu.borrow().as_ref() {
+            Some(lu) => match lu.hint(&system) {
+                Ok(hint) => (
+                    Some(hint),
+                    Some(tr_format(
+                        "Next: {}",
+                        &[&describe_operation(&system, hint, self.number_format.get())],
+                    )),
+                    String::new(),
+                ),
+                Err(reason) => (None, None, reason),
+            },
+            None => match self.field_system.borrow().as_ref() {
+                Some(field_system) => (
+                    field_system.hint(),
+                    field_system.explain_hint(self.number_format.get()),
+                    tr("The system is already in reduced row echelon form.").to_string(),
+                ),
+                None => (
+                    system.hint(),
+                    explain_hint(&system, self.number_format.get()),
+                    tr("The system is already in reduced row echelon form.").to_string(),
+                ),
+            },
+        };
+        if hint.is_some() && self.hint.get() != hint {
+            self.record_hint();
+        }
+        self.hint.set(hint);
+        self.status
+            .set_label(explanation.as_deref().unwrap_or(&done));
+        self.status.set_visible(true);
+        self.drawing_area.set_tooltip_text(explanation.as_deref());
+        self.drawing_area.queue_draw();
+    }
+    pub fn clear_hint(&self) {
+        self.hint.set(None);
+        self.status.set_visible(false);
+        self.drawing_area.set_tooltip_text(None);
+    }
+    pub fn new_random_system(&self) {
+        let system = System::random(
+            self.size.get(),
+            self.difficulty.get(),
+            &mut *self.rng.borrow_mut(),
+        );
+        self.set_system(system, None);
+    }
+    ///Puts a system that isn't made of real numbers on the board, with its stand-in from `to_real`
+    ///as `system`.
+    pub(crate) fn set_field_system(&self, system: FieldSystem) {
+        self.set_system(system.to_real(), None);
+        self.fraction_seen.set(system.has_fraction());
+        *self.field_system.borrow_mut() = Some(system);
+    }
+    ///Checks if the system on the board is solved, using the actual system if it isn't made of
+    ///real numbers.
+    pub fn is_solved(&self) -> bool {
+        match self.field_system.borrow().as_ref() {
+            Some(system) => system.is_solved(),
+            None => self.system.borrow().solution().is_some(),
+        }
+    }
+    ///Checks if there's a fraction on the board, using the actual system if it isn't made of real
+    ///numbers.
+    pub(crate) fn has_fraction(&self) -> bool {
+        match self.field_system.borrow().as_ref() {
+            Some(system) => system.has_fraction(),
+            None => has_fraction(&self.system.borrow()),
+        }
+    }
+    ///Checks if the operation can be done to the system on the board and would change something,
+    ///using the actual system if it isn't made of real numbers.
+    pub fn should_apply(&self, operation: RowOperation) -> bool {
+        match self.field_system.borrow().as_ref() {
+            Some(system) => system.should_apply(operation),
+            None => self.system.borrow().should_apply(operation),
+        }
+    }
+    pub(crate) fn check_field_system(&self, operation: RowOperation) -> Result<(), String> {
+        match self.field_system.borrow().as_ref() {
+            Some(system) => system.check(operation),
+            None => Ok(()),
+        }
+    }
+    ///Does the operation to the system if it isn't made of real numbers and updates `system` to
+    ///match. Returns whether it did.
+    pub(crate) fn apply_field_system(&self, operation: RowOperation) -> bool {
+        let mut field_system = self.field_system.borrow_mut();
+        let Some(system) = field_system.as_mut() else {
+            return false;
+        };
+        system.apply(operation);
+        *self.system.borrow_mut() = system.to_real();
+        if let Some(text) = system.describe_solution(self.number_format.get()) {
+            self.hud.set_label(&text);
+        }
+        true
+    }
+}
@@ -0,0 +1,352 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!The panel that graphs each equation, as a line for 2 unknowns or a plane for 3. Row operations
+//!change the equations but not where they all meet, which is drawn as a dot in the middle.
+use super::*;
+use std::f64::consts::FRAC_PI_2;
+const GRAPH_SIZE: i32 = 300;
+///How far the graph goes from its center along each axis
+const GRAPH_RANGE: f64 = 6.0;
+///The color of each equation's line or plane
+const ROW_COLORS: [(f64, f64, f64); MAX_SYSTEM_SIZE] = [
+    (0.85, 0.1, 0.1),
+    (0.1, 0.4, 0.85),
+    (0.1, 0.6, 0.2),
+    (0.8, 0.5, 0.0),
+    (0.6, 0.2, 0.7),
+    (0.0, 0.6, 0.6),
+    (0.5, 0.35, 0.2),
+    (0.85, 0.3, 0.6),
+];
+pub fn build_geometry_panel() -> DrawingArea {
+    DrawingArea::builder()
+        .width_request(GRAPH_SIZE)
+        .height_request(GRAPH_SIZE)
+        .margin_top(10)
+        .margin_bottom(10)
+        .margin_end(10)
+        .visible(false)
+        .build()
+}
+///Which way the graph of a system with 3 unknowns is looked at from, in radians
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Orbit {
+    ///Around the third unknown's axis
+    pub yaw: f64,
+    ///How far above the plane of the first two unknowns
+    pub pitch: f64,
+}
+impl Default for Orbit {
+    fn default() -> Self {
+        Self {
+            yaw: 0.6,
+            pitch: 0.4,
+        }
+    }
+}
+impl Orbit {
+    ///Turns the view by a drag of `(x, y)` pixels.
+    pub fn turned(self, x: f64, y: f64) -> Self {
+        Self {
+            yaw: self.yaw + x * 0.01,
+            pitch: (self.pitch + y * 0.01).clamp(-FRAC_PI_2, FRAC_PI_2),
+        }
+    }
+    ///Projects a point onto the screen without perspective. The third unknown is up. The result
+    ///is right and up from the center of the graph.
+    fn project(self, point: [f64; 3]) -> (f64, f64) {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let right = point[0] * cos_yaw - point[1] * sin_yaw;
+        let away = point[0] * sin_yaw + point[1] * cos_yaw;
+        (right, point[2] * cos_pitch + away * sin_pitch)
+    }
+}
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+///The corners of the part of the plane `normal`·p = `offset` inside the cube from -`range` to
+///`range`, in order around its edge
+fn plane_in_cube(normal: [f64; 3], offset: f64, range: f64) -> Vec<[f64; 3]> {
+    let mut corners: Vec<[f64; 3]> = Vec::new();
+    for axis in (0..3).filter(|axis| normal[*axis] != 0.0) {
+        for a in [-range, range] {
+            for b in [-range, range] {
+                //The edge of the cube along `axis` at `a` and `b` on the other two axes
+                let mut start = [0.0; 3];
+                start[(axis + 1) % 3] = a;
+                start[(axis + 2) % 3] = b;
+                start[axis] = -range;
+                let along = (offset - dot(normal, start)) / normal[axis];
+                if (0.0..=2.0 * range).contains(&along) {
+                    let mut corner = start;
+                    corner[axis] += along;
+                    if !corners
+                        .iter()
+                        .any(|other| (0..3).all(|i| (other[i] - corner[i]).abs() < 1e-9))
+                    {
+                        corners.push(corner);
+                    }
+                }
+            }
+        }
+    }
+    if corners.len() < 3 {
+        return Vec::new();
+    }
+    let middle = [0, 1, 2]
+        .map(|i| corners.iter().map(|corner| corner[i]).sum::<f64>())
+        .map(|total| total / corners.len() as f64);
+    //Two directions along the plane to measure angles around the middle with, starting from the
+    //axis the plane is least perpendicular to so that they aren't too short
+    let least = (0..3)
+        .min_by(|a, b| normal[*a].abs().total_cmp(&normal[*b].abs()))
+        .unwrap();
+    let mut axis = [0.0; 3];
+    axis[least] = 1.0;
+    let across = cross(normal, axis);
+    let down = cross(normal, across);
+    let angle = |corner: &[f64; 3]| {
+        let offset = [0, 1, 2].map(|i| corner[i] - middle[i]);
+        dot(offset, down).atan2(dot(offset, across))
+    };
+    corners.sort_by(|a, b| angle(a).total_cmp(&angle(b)));
+    corners
+}
+fn draw_lines(
+    context: &gtk4::cairo::Context,
+    width: f64,
+    height: f64,
+    system: &System,
+    center: &[f64],
+) {
+    let scale = width.min(height) / (2.0 * GRAPH_RANGE);
+    let to_screen = |x: f64, y: f64| {
+        (
+            width / 2.0 + (x - center[0]) * scale,
+            height / 2.0 - (y - center[1]) * scale,
+        )
+    };
+    context.set_source_rgb(0.75, 0.75, 0.75);
+    let (left, bottom) = to_screen(center[0] - GRAPH_RANGE, center[1] - GRAPH_RANGE);
+    let (right, top) = to_screen(center[0] + GRAPH_RANGE, center[1] + GRAPH_RANGE);
+    let (origin_x, origin_y) = to_screen(0.0, 0.0);
+    context.move_to(left, origin_y);
+    context.line_to(right, origin_y);
+    context.move_to(origin_x, bottom);
+    context.line_to(origin_x, top);
+    context.stroke().unwrap();
+    context.set_line_width(2.0);
+    for (equation, color) in system.equations.iter().zip(ROW_COLORS) {
+        let (a, b) = (equation.coefficients[0], equation.coefficients[1]);
+        let c = equation.solution;
+        //Going across whichever way the line is closer to lets steep lines reach the edges too.
+        let (start, end) = if a == 0.0 && b == 0.0 {
+            continue;
+        } else if b.abs() >= a.abs() {
+            let x = [center[0] - GRAPH_RANGE, center[0] + GRAPH_RANGE];
+            (
+                to_screen(x[0], (c - a * x[0]) / b),
+                to_screen(x[1], (c - a * x[1]) / b),
+            )
+        } else {
+            let y = [center[1] - GRAPH_RANGE, center[1] + GRAPH_RANGE];
+            (
+                to_screen((c - b * y[0]) / a, y[0]),
+                to_screen((c - b * y[1]) / a, y[1]),
+            )
+        };
+        context.set_source_rgb(color.0, color.1, color.2);
+        context.move_to(start.0, start.1);
+        context.line_to(end.0, end.1);
+        context.stroke().unwrap();
+    }
+    context.set_line_width(1.0);
+}
+fn draw_planes(
+    context: &gtk4::cairo::Context,
+    width: f64,
+    height: f64,
+    system: &System,
+    center: &[f64],
+    orbit: Orbit,
+) {
+    //Leaves room for the corners of the cube as it turns
+    let scale = width.min(height) / (2.0 * GRAPH_RANGE * 3.0_f64.sqrt());
+    let to_screen = |point: [f64; 3]| {
+        let (right, up) = orbit.project(point);
+        (width / 2.0 + right * scale, height / 2.0 - up * scale)
+    };
+    context.set_source_rgb(0.75, 0.75, 0.75);
+    for axis in 0..3 {
+        for a in [-GRAPH_RANGE, GRAPH_RANGE] {
+            for b in [-GRAPH_RANGE, GRAPH_RANGE] {
+                let mut start = [0.0; 3];
+                start[(axis + 1) % 3] = a;
+                start[(axis + 2) % 3] = b;
+                start[axis] = -GRAPH_RANGE;
+                let mut end = start;
+                end[axis] = GRAPH_RANGE;
+                let (start_x, start_y) = to_screen(start);
+                let (end_x, end_y) = to_screen(end);
+                context.move_to(start_x, start_y);
+                context.line_to(end_x, end_y);
+            }
+        }
+    }
+    context.stroke().unwrap();
+    for (equation, color) in system.equations.iter().zip(ROW_COLORS) {
+        let normal = [0, 1, 2].map(|i| equation.coefficients[i]);
+        let offset = equation.solution - (0..3).map(|i| normal[i] * center[i]).sum::<f64>();
+        let corners = plane_in_cube(normal, offset, GRAPH_RANGE);
+        if corners.is_empty() {
+            continue;
+        }
+        for corner in corners.iter() {
+            let (x, y) = to_screen(*corner);
+            context.line_to(x, y);
+        }
+        context.close_path();
+        context.set_source_rgba(color.0, color.1, color.2, 0.25);
+        context.fill_preserve().unwrap();
+        context.set_source_rgb(color.0, color.1, color.2);
+        context.stroke().unwrap();
+    }
+    //Shows which way each unknown's axis points
+    context.set_source_rgb(0.3, 0.3, 0.3);
+    context.set_font_size(12.0);
+    for (axis, name) in ["x₁", "x₂", "x₃"].iter().enumerate() {
+        let mut direction = [0.0; 3];
+        direction[axis] = 1.0;
+        let (right, up) = orbit.project(direction);
+        let (x, y) = (30.0 + right * 20.0, height - 30.0 - up * 20.0);
+        context.move_to(30.0, height - 30.0);
+        context.line_to(x, y);
+        context.stroke().unwrap();
+        draw_text_centered(
+            context,
+            30.0 + right * 28.0,
+            height - 30.0 - up * 28.0,
+            name,
+        );
+    }
+    let text = tr("Drag to turn");
+    let extents = context.text_extents(text).unwrap();
+    context.move_to(width - extents.width() - 8.0, height - 8.0);
+    context.show_text(text).unwrap();
+}
+///Graphs the equations centered on the point where they meet, or the origin if they don't meet
+///in just one point.
+pub fn draw_geometry(
+    context: &gtk4::cairo::Context,
+    width: f64,
+    height: f64,
+    system: &System,
+    orbit: Orbit,
+) {
+    context.set_source_rgb(1.0, 1.0, 1.0);
+    context.paint().unwrap();
+    let size = system.size();
+    context.set_font_size(14.0);
+    if !(2..=3).contains(&size) {
+        context.set_source_rgb(0.0, 0.0, 0.0);
+        draw_text_centered(
+            context,
+            width / 2.0,
+            height / 2.0,
+            tr("Only 2 or 3 unknowns"),
+        );
+        return;
+    }
+    let solution = system.unique_solution();
+    let center = solution.clone().unwrap_or_else(|| vec![0.0; size]);
+    context.save().unwrap();
+    context.rectangle(0.0, 0.0, width, height);
+    context.clip();
+    if size == 2 {
+        draw_lines(context, width, height, system, &center);
+    } else {
+        draw_planes(context, width, height, system, &center, orbit);
+    }
+    context.restore().unwrap();
+    if solution.is_some() {
+        context.set_source_rgb(0.0, 0.0, 0.0);
+        context.arc(
+            width / 2.0,
+            height / 2.0,
+            4.0,
+            0.0,
+            std::f64::consts::PI * 2.0,
+        );
+        context.fill().unwrap();
+    }
+    context.set_font_size(14.0);
+    for (i, color) in ROW_COLORS.iter().take(size).enumerate() {
+        context.set_source_rgb(color.0, color.1, color.2);
+        context.move_to(8.0, 20.0 + 18.0 * i as f64);
+        context.show_text(&format!("R{}", i + 1)).unwrap();
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    ///Checks that the corners are on the plane, in the cube, and go around the edge in order, so
+    ///every turn from one side to the next is the same way around the normal.
+    fn check_polygon(corners: &[[f64; 3]], normal: [f64; 3], offset: f64, range: f64) {
+        for corner in corners {
+            assert!((dot(normal, *corner) - offset).abs() < 1e-9, "{:?}", corner);
+            assert!(
+                corner.iter().all(|x| x.abs() <= range + 1e-9),
+                "{:?}",
+                corner
+            );
+        }
+        let turns: Vec<f64> = (0..corners.len())
+            .map(|i| {
+                let [a, b, c] = [0, 1, 2].map(|j| corners[(i + j) % corners.len()]);
+                let side = [0, 1, 2].map(|k| b[k] - a[k]);
+                let next_side = [0, 1, 2].map(|k| c[k] - b[k]);
+                dot(cross(side, next_side), normal)
+            })
+            .collect();
+        assert!(
+            turns.iter().all(|turn| *turn > 0.0) || turns.iter().all(|turn| *turn < 0.0),
+            "{:?}",
+            corners
+        );
+    }
+    #[test]
+    fn cuts_squares_and_hexagons() {
+        let corners = plane_in_cube([0.0, 0.0, 1.0], 0.0, 1.0);
+        assert_eq!(corners.len(), 4);
+        check_polygon(&corners, [0.0, 0.0, 1.0], 0.0, 1.0);
+        let corners = plane_in_cube([1.0, 1.0, 1.0], 0.0, 2.0);
+        assert_eq!(corners.len(), 6);
+        check_polygon(&corners, [1.0, 1.0, 1.0], 0.0, 2.0);
+        let normal = [2.0, -1.0, 0.5];
+        let corners = plane_in_cube(normal, 3.0, GRAPH_RANGE);
+        assert!(corners.len() >= 3);
+        check_polygon(&corners, normal, 3.0, GRAPH_RANGE);
+    }
+    #[test]
+    fn leaves_out_planes_that_miss_the_cube() {
+        assert!(plane_in_cube([1.0, 0.0, 0.0], 10.0, 6.0).is_empty());
+        //Only touches one corner
+        assert!(plane_in_cube([1.0, 1.0, 1.0], 3.0, 1.0).is_empty());
+        assert!(plane_in_cube([0.0, 0.0, 0.0], 0.0, 1.0).is_empty());
+    }
+    #[test]
+    fn turns_within_limits() {
+        let orbit = Orbit::default().turned(100.0, 1000.0);
+        assert_eq!(orbit.pitch, FRAC_PI_2);
+        assert!((orbit.yaw - 1.6).abs() < 1e-12);
+        assert_eq!(Orbit::default().project([0.0, 0.0, 1.0]).0, 0.0);
+    }
+}
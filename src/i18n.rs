@@ -0,0 +1,269 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!Translations of the window's text, kept in gettext `.po` files in the `po` directory and built
+//!into the game. The language is chosen from the `LANGUAGE`, `LC_ALL`, `LC_MESSAGES`, and `LANG`
+//!environment variables the way gettext does, and anything without a translation is shown in
+//!English. Numbers use the decimal separator of `LC_ALL`, `LC_NUMERIC`, or `LANG`.
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Language {
+    English,
+    Spanish,
+}
+impl Language {
+    ///Finds the language of a locale like `es_MX.UTF-8`, or `None` if there isn't a translation
+    ///for it.
+    fn from_locale(locale: &str) -> Option<Self> {
+        match language_code(locale) {
+            "en" | "C" | "POSIX" => Some(Self::English),
+            "es" => Some(Self::Spanish),
+            _ => None,
+        }
+    }
+    fn catalog_source(self) -> &'static str {
+        match self {
+            Self::English => "",
+            Self::Spanish => include_str!("../po/es.po"),
+        }
+    }
+}
+///The part of a locale before the country, encoding, and modifier
+fn language_code(locale: &str) -> &str {
+    locale.split(['_', '.', '@']).next().unwrap_or(locale)
+}
+///The value of the first of the environment variables that is set and not empty
+fn locale_variable(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+pub fn language() -> Language {
+    //Tests check the English text whatever the environment they run in.
+    if cfg!(test) {
+        return Language::English;
+    }
+    static LANGUAGE: OnceLock<Language> = OnceLock::new();
+    *LANGUAGE.get_or_init(|| {
+        //Like gettext, LANGUAGE is a list of languages to try in order and is only used if the
+        //locale isn't C.
+        let locale = locale_variable(&["LC_ALL", "LC_MESSAGES", "LANG"]).unwrap_or_default();
+        if !locale.is_empty()
+            && locale != "C"
+            && let Some(language) = std::env::var("LANGUAGE")
+                .unwrap_or_default()
+                .split(':')
+                .find_map(Language::from_locale)
+        {
+            return language;
+        }
+        Language::from_locale(&locale).unwrap_or(Language::English)
+    })
+}
+///Reads a quoted `.po` string, undoing its escapes.
+fn parse_po_string(quoted: &str) -> String {
+    let mut string = String::new();
+    let mut characters = quoted
+        .trim()
+        .trim_start_matches('"')
+        .trim_end_matches('"')
+        .chars();
+    while let Some(character) = characters.next() {
+        if character == '\\' {
+            match characters.next() {
+                Some('n') => string.push('\n'),
+                Some('t') => string.push('\t'),
+                Some(other) => string.push(other),
+                None => {}
+            }
+        } else {
+            string.push(character);
+        }
+    }
+    string
+}
+///Reads the `msgid` and `msgstr` pairs of a `.po` file, including strings split across lines.
+///Entries with an empty `msgstr` aren't translated yet and are left out, and so are entries marked
+///`#, fuzzy`, which gettext doesn't use until someone checks them.
+fn parse_po(source: &str) -> HashMap<String, String> {
+    let mut catalog = HashMap::new();
+    let mut id = String::new();
+    let mut translation = String::new();
+    //Which of the two continuation lines are added to
+    let mut in_translation = false;
+    //Whether the flags before the next entry or the current one say it's fuzzy
+    let mut next_fuzzy = false;
+    let mut fuzzy = false;
+    let mut add_entry = |id: &mut String, translation: &mut String, fuzzy: bool| {
+        if !id.is_empty() && !translation.is_empty() && !fuzzy {
+            catalog.insert(std::mem::take(id), std::mem::take(translation));
+        }
+        id.clear();
+        translation.clear();
+    };
+    for line in source.lines() {
+        let line = line.trim();
+        if let Some(flags) = line.strip_prefix("#,") {
+            if flags.split(',').any(|flag| flag.trim() == "fuzzy") {
+                next_fuzzy = true;
+            }
+        } else if let Some(rest) = line.strip_prefix("msgid ") {
+            add_entry(&mut id, &mut translation, fuzzy);
+            id = parse_po_string(rest);
+            in_translation = false;
+            fuzzy = next_fuzzy;
+            next_fuzzy = false;
+        } else if let Some(rest) = line.strip_prefix("msgstr ") {
+            translation = parse_po_string(rest);
+            in_translation = true;
+        } else if line.starts_with('"') {
+            let continuation = parse_po_string(line);
+            if in_translation {
+                translation.push_str(&continuation);
+            } else {
+                id.push_str(&continuation);
+            }
+        }
+    }
+    add_entry(&mut id, &mut translation, fuzzy);
+    catalog
+}
+///Translates text into the player's language.
+pub fn tr(text: &str) -> &str {
+    static CATALOG: OnceLock<HashMap<String, String>> = OnceLock::new();
+    CATALOG
+        .get_or_init(|| parse_po(language().catalog_source()))
+        .get(text)
+        .map_or(text, String::as_str)
+}
+///Translates text and then fills in each `{}` with the next argument, since translations can't
+///go through `format!`.
+pub fn tr_format(template: &str, arguments: &[&dyn Display]) -> String {
+    fill_template(tr(template), arguments)
+}
+///Fills in each `{}` in the template with the next argument without translating it.
+pub fn fill_template(template: &str, arguments: &[&dyn Display]) -> String {
+    let mut pieces = template.split("{}");
+    let mut text = pieces.next().unwrap_or_default().to_string();
+    for (piece, argument) in pieces.zip(arguments) {
+        text.push_str(&argument.to_string());
+        text.push_str(piece);
+    }
+    text
+}
+///Changes the decimal point in a formatted number to the locale's decimal separator.
+pub fn localize_decimal(text: String) -> String {
+    replace_decimal_point(text, decimal_separator())
+}
+fn replace_decimal_point(text: String, separator: char) -> String {
+    match separator {
+        '.' => text,
+        separator => text.replace('.', &separator.to_string()),
+    }
+}
+///The character between the whole part and the fractional part of a decimal, which is a comma in
+///most of the world
+pub fn decimal_separator() -> char {
+    if cfg!(test) {
+        return '.';
+    }
+    static SEPARATOR: OnceLock<char> = OnceLock::new();
+    *SEPARATOR.get_or_init(|| {
+        locale_decimal_separator(
+            &locale_variable(&["LC_ALL", "LC_NUMERIC", "LANG"]).unwrap_or_default(),
+        )
+    })
+}
+///The decimal separator of a locale like `de_DE.UTF-8`
+fn locale_decimal_separator(locale: &str) -> char {
+    const COMMA: [&str; 30] = [
+        "bg", "ca", "cs", "da", "de", "el", "es", "et", "eu", "fi", "fr", "gl", "hr", "hu", "id",
+        "is", "it", "lt", "lv", "nb", "nl", "nn", "pl", "pt", "ro", "ru", "sk", "sl", "sv", "tr",
+    ];
+    //Spanish in Mexico and a few other countries uses a point.
+    let point_countries = [
+        "es_MX", "es_US", "es_GT", "es_HN", "es_NI", "es_PA", "es_PR", "es_SV", "es_DO",
+    ];
+    if COMMA.contains(&language_code(locale))
+        && !point_countries
+            .iter()
+            .any(|country| locale.starts_with(country))
+    {
+        ','
+    } else {
+        '.'
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parses_po_files() {
+        let catalog = parse_po(
+            r#"msgid ""
+msgstr ""
+"Language: es\n"
+
+msgid "Say \"hi\"\tnow\n"
+msgstr "Di \"hola\"\tahora\n"
+
+msgid ""
+"Split "
+"across lines"
+msgstr "Partido "
+"en líneas"
+
+#, fuzzy
+msgid "Not checked yet"
+msgstr "Sin revisar"
+
+#, c-format, fuzzy
+msgid "Also not checked"
+msgstr "Tampoco revisado"
+
+# A translator's note
+#, c-format
+msgid "Checked"
+msgstr "Revisado"
+
+msgid "Not translated"
+msgstr ""
+"#,
+        );
+        assert_eq!(catalog.len(), 3, "{:?}", catalog);
+        assert_eq!(catalog["Say \"hi\"\tnow\n"], "Di \"hola\"\tahora\n");
+        assert_eq!(catalog["Split across lines"], "Partido en líneas");
+        assert_eq!(catalog["Checked"], "Revisado");
+        assert!(!catalog.contains_key("Not checked yet"));
+        assert!(!catalog.contains_key("Also not checked"));
+        assert!(!catalog.contains_key("Not translated"));
+    }
+    #[test]
+    fn reads_the_spanish_catalog() {
+        let catalog = parse_po(Language::Spanish.catalog_source());
+        assert_eq!(catalog["New"], "Nuevo");
+        assert!(catalog.keys().all(|id| !id.is_empty()));
+        for (id, translation) in catalog.iter() {
+            assert_eq!(
+                id.matches("{}").count(),
+                translation.matches("{}").count(),
+                "{}",
+                id
+            );
+        }
+    }
+    #[test]
+    fn finds_decimal_separators() {
+        assert_eq!(locale_decimal_separator("de_DE.UTF-8"), ',');
+        assert_eq!(locale_decimal_separator("es_ES.UTF-8"), ',');
+        assert_eq!(locale_decimal_separator("es_MX.UTF-8"), '.');
+        assert_eq!(locale_decimal_separator("en_US.UTF-8"), '.');
+        assert_eq!(locale_decimal_separator("C"), '.');
+        assert_eq!(locale_decimal_separator(""), '.');
+        assert_eq!(replace_decimal_point("-1.25".to_string(), ','), "-1,25");
+        assert_eq!(replace_decimal_point("-1.25".to_string(), '.'), "-1.25");
+        assert_eq!(replace_decimal_point("3".to_string(), ','), "3");
+    }
+}
@@ -0,0 +1,166 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+use super::*;
+//Lets bmatrix take an optional column specification so that it can have an augmentation bar.
+const PREAMBLE: &str = r"\documentclass{article}
+\usepackage{amsmath}
+\allowdisplaybreaks
+\makeatletter
+\renewcommand*\env@matrix[1][*\c@MaxMatrixCols c]{%
+  \hskip -\arraycolsep
+  \let\@ifnextchar\new@ifnextchar
+  \array{#1}}
+\makeatother
+\begin{document}
+";
+fn latex_number(float: f64) -> String {
+    match approximate_fraction(float) {
+        Some((numerator, 1)) => numerator.to_string(),
+        Some((numerator, denominator)) if numerator < 0 => {
+            format!("-\\frac{{{}}}{{{}}}", -numerator, denominator)
+        }
+        Some((numerator, denominator)) => format!("\\frac{{{}}}{{{}}}", numerator, denominator),
+        None => format_float_with_point(float),
+    }
+}
+///Like `latex_number` but for a factor in front of something else, so 1 is left out.
+fn latex_factor(float: f64) -> String {
+    if float == 1.0 {
+        String::new()
+    } else if float == -1.0 {
+        "-".to_string()
+    } else {
+        latex_number(float)
+    }
+}
+fn latex_row(row: usize) -> String {
+    format!("R_{{{}}}", row + 1)
+}
+fn latex_matrix(system: &System) -> String {
+    let mut matrix = format!("\\begin{{bmatrix}}[{}|c]\n", "c".repeat(system.size()));
+    for equation in system.equations.iter() {
+        for coefficient in equation.coefficients.iter() {
+            matrix.push_str(&latex_number(*coefficient));
+            matrix.push_str(" & ");
+        }
+        matrix.push_str(&latex_number(equation.solution));
+        matrix.push_str(" \\\\\n");
+    }
+    matrix.push_str("\\end{bmatrix}");
+    matrix
+}
+///Describes the operation as it would be applied to `system`.
+fn latex_operation(system: &System, operation: RowOperation) -> String {
+    match operation {
+        RowOperation::SwitchRows(a, b) => {
+            format!("{} \\leftrightarrow {}", latex_row(a), latex_row(b))
+        }
+        RowOperation::MakeCoefficient1(equation, coefficient) => {
+            let factor = 1.0 / system.equations[equation].coefficients[coefficient];
+            format!(
+                "{}{} \\to {}",
+                latex_factor(factor),
+                latex_row(equation),
+                latex_row(equation)
+            )
+        }
+        RowOperation::MakeCoefficient0WithRow(equation, coefficient, with) => {
+            let factor = system.equations[equation].coefficients[coefficient]
+                / system.equations[with].coefficients[coefficient];
+            let sign = if factor < 0.0 { '+' } else { '-' };
+            format!(
+                "{} {} {}{} \\to {}",
+                latex_row(equation),
+                sign,
+                latex_factor(factor.abs()),
+                latex_row(with),
+                latex_row(equation)
+            )
+        }
+    }
+}
+///Writes a complete LaTeX document showing `start` and the matrix after each operation, with
+///arrows labeled with the operations between them.
+pub fn export_latex(start: &System, operations: &[RowOperation]) -> String {
+    let mut document = PREAMBLE.to_string();
+    document.push_str("\\begin{align*}\n&");
+    let mut system = start.clone();
+    document.push_str(&latex_matrix(&system));
+    for operation in operations {
+        document.push_str("\\\\\n\\xrightarrow{");
+        document.push_str(&latex_operation(&system, *operation));
+        document.push_str("}&");
+        system.apply(*operation);
+        document.push_str(&latex_matrix(&system));
+    }
+    document.push_str("\n\\end{align*}\n\\end{document}\n");
+    document
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn system() -> System {
+        System::new(vec![
+            Equation::new(vec![2.0, 1.0], 5.0),
+            Equation::new(vec![4.0, 3.0], 11.0),
+        ])
+    }
+    #[test]
+    fn approximates_fractions() {
+        assert_eq!(approximate_fraction(0.0), Some((0, 1)));
+        assert_eq!(approximate_fraction(3.0), Some((3, 1)));
+        assert_eq!(approximate_fraction(1.0 / 3.0), Some((1, 3)));
+        assert_eq!(approximate_fraction(-22.0 / 7.0), Some((-22, 7)));
+        assert_eq!(approximate_fraction(0.1 + 0.2), Some((3, 10)));
+        assert_eq!(approximate_fraction(std::f64::consts::PI), None);
+        assert_eq!(approximate_fraction(f64::NAN), None);
+        assert_eq!(approximate_fraction(f64::INFINITY), None);
+        assert_eq!(approximate_fraction(1e13), None);
+    }
+    #[test]
+    fn formats_numbers() {
+        assert_eq!(latex_number(4.0), "4");
+        assert_eq!(latex_number(0.5), "\\frac{1}{2}");
+        assert_eq!(latex_number(-2.0 / 3.0), "-\\frac{2}{3}");
+        assert_eq!(latex_factor(1.0), "");
+        assert_eq!(latex_factor(-1.0), "-");
+        assert_eq!(latex_factor(3.0), "3");
+    }
+    #[test]
+    fn describes_operations() {
+        let system = system();
+        assert_eq!(
+            latex_operation(&system, RowOperation::SwitchRows(0, 1)),
+            "R_{1} \\leftrightarrow R_{2}"
+        );
+        assert_eq!(
+            latex_operation(&system, RowOperation::MakeCoefficient1(0, 0)),
+            "\\frac{1}{2}R_{1} \\to R_{1}"
+        );
+        assert_eq!(
+            latex_operation(&system, RowOperation::MakeCoefficient0WithRow(1, 0, 0)),
+            "R_{2} - 2R_{1} \\to R_{2}"
+        );
+        let mut negative = system.clone();
+        negative.equations[1].coefficients[0] = -2.0;
+        assert_eq!(
+            latex_operation(&negative, RowOperation::MakeCoefficient0WithRow(1, 0, 0)),
+            "R_{2} + R_{1} \\to R_{2}"
+        );
+    }
+    #[test]
+    fn exports_each_step() {
+        let operations = [
+            RowOperation::MakeCoefficient0WithRow(1, 0, 0),
+            RowOperation::MakeCoefficient1(0, 0),
+        ];
+        let document = export_latex(&system(), &operations);
+        assert!(document.starts_with(PREAMBLE));
+        assert!(document.ends_with("\\end{align*}\n\\end{document}\n"));
+        assert_eq!(document.matches("\\begin{bmatrix}[cc|c]").count(), 3);
+        assert_eq!(document.matches("\\xrightarrow").count(), 2);
+        //The matrices after each operation
+        assert!(document.contains("2 & 1 & 5 \\\\\n0 & 1 & 1 \\\\"));
+        assert!(document.contains("1 & \\frac{1}{2} & \\frac{5}{2} \\\\\n0 & 1 & 1 \\\\"));
+    }
+}
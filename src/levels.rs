@@ -0,0 +1,329 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!Level packs are puzzle files split into levels. Each level starts with a `level` line with its
+//!title, followed by any number of `note` lines explaining it and `rule` lines restricting what the
+//!player can do, then the system in the usual puzzle file format:
+//!```text
+//!level No Swapping
+//!note The top left coefficient is 0, but swapping isn't allowed here.
+//!rule no-swaps
+//!0 2 | 6
+//!1 1 | 4
+//!```
+//!The rules are `no-swaps`, `no-scaling`, and `max-moves N`. A level is complete when the system
+//!is in reduced row echelon form, so levels can have systems without a unique solution.
+use super::*;
+use gtk4::ListBox;
+const CAMPAIGN: &str = include_str!("../levels/campaign.txt");
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rule {
+    NoSwaps,
+    NoScaling,
+    ///No more than this many operations in total
+    MaxMoves(usize),
+}
+impl Rule {
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut words = text.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some("no-swaps"), None, None) => Ok(Self::NoSwaps),
+            (Some("no-scaling"), None, None) => Ok(Self::NoScaling),
+            (Some("max-moves"), Some(moves), None) => moves
+                .parse()
+                .map(Self::MaxMoves)
+                .map_err(|_| format!("{:?} is not a number of moves", moves)),
+            _ => Err(format!(
+                "unknown rule {:?}, expected no-swaps, no-scaling, or max-moves N",
+                text
+            )),
+        }
+    }
+    pub fn describe(self) -> String {
+        match self {
+            Self::NoSwaps => tr("No swapping rows").to_string(),
+            Self::NoScaling => tr("No scaling rows").to_string(),
+            Self::MaxMoves(moves) => tr_format("At most {} operations", &[&moves]),
+        }
+    }
+    ///Checks if `operation` can be done after `moves` operations, and says why not if it can't.
+    fn check(self, operation: RowOperation, moves: usize) -> Result<(), String> {
+        match (self, operation) {
+            (Self::NoSwaps, RowOperation::SwitchRows(..)) => {
+                Err(tr("Swapping rows isn't allowed in this level.").to_string())
+            }
+            (Self::NoScaling, RowOperation::MakeCoefficient1(..)) => {
+                Err(tr("Scaling rows isn't allowed in this level.").to_string())
+            }
+            (Self::MaxMoves(max_moves), _) if moves >= max_moves => Err(tr_format(
+                "This level has to be solved in {} operations. Choose it in Levels to try again.",
+                &[&max_moves],
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level {
+    pub title: String,
+    ///Each note is a paragraph.
+    pub notes: Vec<String>,
+    pub rules: Vec<Rule>,
+    pub system: System,
+}
+///Reads a level pack. Errors say which level they're in.
+pub fn parse_levels(text: &str) -> Result<Vec<Level>, String> {
+    let mut levels = Vec::new();
+    //The level being read and the lines of its system
+    let mut current: Option<(Level, String)> = None;
+    let finish = |current: Option<(Level, String)>, levels: &mut Vec<Level>| {
+        if let Some((mut level, system_text)) = current {
+            level.system = parse_system(&system_text)
+                .map_err(|error| format!("level {:?}: {}", level.title, error))?;
+            levels.push(level);
+        }
+        Ok::<(), String>(())
+    };
+    for (i, line) in text.lines().enumerate() {
+        if let Some(title) = line.strip_prefix("level ") {
+            finish(current.take(), &mut levels)?;
+            let level = Level {
+                title: title.trim().to_string(),
+                notes: Vec::new(),
+                rules: Vec::new(),
+                system: System::default(),
+            };
+            current = Some((level, String::new()));
+        } else if let Some((level, system_text)) = current.as_mut() {
+            if let Some(note) = line.strip_prefix("note ") {
+                level.notes.push(note.trim().to_string());
+            } else if let Some(rule) = line.strip_prefix("rule ") {
+                level.rules.push(
+                    Rule::parse(rule)
+                        .map_err(|error| format!("level {:?}: {}", level.title, error))?,
+                );
+            } else {
+                system_text.push_str(line);
+                system_text.push('\n');
+            }
+        } else if !line.trim().is_empty() && !line.trim_start().starts_with('#') {
+            return Err(format!(
+                "line {}: expected a level line before anything else",
+                i + 1
+            ));
+        }
+    }
+    finish(current, &mut levels)?;
+    Ok(levels)
+}
+///The levels bundled with the game
+pub fn campaign() -> Vec<Level> {
+    parse_levels(CAMPAIGN).expect("the bundled campaign should be valid")
+}
+impl Game {
+    pub fn start_level(&self, index: usize) {
+        self.stop_run();
+        let level = &self.levels[index];
+        self.set_system(level.system.clone(), None);
+        self.level.set(Some(index));
+        self.update_level_hud();
+    }
+    pub(crate) fn update_level_hud(&self) {
+        let Some(index) = self.level.get() else {
+            return;
+        };
+        let level = &self.levels[index];
+        let mut text = tr_format("Level {}: {}", &[&(index + 1), &tr(&level.title)]);
+        for note in level.notes.iter() {
+            text.push_str("\n\n");
+            text.push_str(tr(note));
+        }
+        for rule in level.rules.iter() {
+            text.push_str("\n\n");
+            text.push_str(&rule.describe());
+            if let Rule::MaxMoves(_) = rule {
+                text.push_str(&tr_format(
+                    " ({} so far)",
+                    &[&self.operations.borrow().len()],
+                ));
+            }
+        }
+        self.hud.set_label(&text);
+        self.hud.set_visible(true);
+    }
+    ///Checks if the player can do `operation` under the current level's rules.
+    pub(crate) fn check_level_rules(&self, operation: RowOperation) -> Result<(), String> {
+        let Some(index) = self.level.get() else {
+            return Ok(());
+        };
+        let moves = self.operations.borrow().len();
+        for rule in self.levels[index].rules.iter() {
+            rule.check(operation, moves)?;
+        }
+        Ok(())
+    }
+    ///Ends the current level without completing it because the computer made moves, which
+    ///wouldn't follow its rules.
+    pub(crate) fn end_level_for_computer(&self) {
+        if self.level.take().is_none() {
+            return;
+        }
+        self.hud.set_visible(false);
+        self.show_toast(tr(
+            "The level ended because the computer took over. Choose it in Levels to try again.",
+        ));
+    }
+    ///Marks the current level complete if the player's last move put the system in reduced row
+    ///echelon form and offers the next one.
+    pub(crate) fn check_level_complete(self: &Rc<Self>) {
+        let Some(index) = self.level.get() else {
+            return;
+        };
+        if self.assisted.get() {
+            return;
+        }
+        let system = self.system.borrow().clone();
+        if !system.is_reduced_row_echelon_form() {
+            return;
+        }
+        self.level.set(None);
+        {
+            let mut stats = self.stats.borrow_mut();
+            stats.completed_levels.insert(index);
+            if let Err(error) = stats.save() {
+                eprintln!("Could not save statistics: {}", error);
+            }
+        }
+        let result = if system.solution().is_some() {
+            "The system has exactly one solution."
+        } else if system.is_inconsistent() {
+            "The system has no solution."
+        } else {
+            "The system has infinitely many solutions."
+        };
+        let dialog = MessageDialog::builder()
+            .message_type(MessageType::Info)
+            .text(tr_format("Level {} complete!", &[&(index + 1)]))
+            .secondary_text(tr(result))
+            .modal(true)
+            .build();
+        dialog.add_button(tr("Close"), ResponseType::Close);
+        if index + 1 < self.levels.len() {
+            dialog.add_button(tr("Next Level"), ResponseType::Accept);
+        }
+        dialog.set_transient_for(self.drawing_area.root().and_downcast_ref::<gtk4::Window>());
+        let my_self = Rc::clone(self);
+        dialog.connect_response(move |dialog, response| {
+            dialog.destroy();
+            if response == ResponseType::Accept {
+                my_self.start_level(index + 1);
+            }
+        });
+        dialog.present();
+    }
+}
+///Opens a window listing the levels. Each level can be played once the one before it is
+///complete.
+pub fn show_level_select(game: &Rc<Game>) {
+    let main_box = gtk4::Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(10)
+        .margin_top(10)
+        .margin_bottom(10)
+        .margin_start(10)
+        .margin_end(10)
+        .build();
+    let completed = game.stats.borrow().completed_levels.clone();
+    let progress = Label::builder()
+        .label(tr_format(
+            "{} of {} levels complete",
+            &[&completed.len(), &game.levels.len()],
+        ))
+        .build();
+    let list = ListBox::new();
+    let window = gtk4::Window::builder()
+        .title(tr("Levels"))
+        .child(&main_box)
+        .default_width(300)
+        .build();
+    for (i, level) in game.levels.iter().enumerate() {
+        let mark = if completed.contains(&i) { " ✓" } else { "" };
+        let button = Button::builder()
+            .label(format!("{}. {}{}", i + 1, tr(&level.title), mark))
+            .sensitive(i == 0 || completed.contains(&(i - 1)))
+            .build();
+        let my_game = Rc::clone(game);
+        let my_window = window.clone();
+        button.connect_clicked(move |_| {
+            my_game.start_level(i);
+            my_window.close();
+        });
+        list.append(&button);
+    }
+    main_box.append(&progress);
+    main_box.append(&list);
+    window.present();
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn bundled_campaign_is_valid() {
+        let levels = parse_levels(CAMPAIGN).unwrap();
+        assert!(!levels.is_empty());
+        for level in levels.iter() {
+            assert!(!level.title.is_empty());
+            assert!(level.system.size() >= 2, "{}", level.title);
+            //A level's own rules have to let it be finished.
+            if let Some(Rule::MaxMoves(moves)) = level
+                .rules
+                .iter()
+                .find(|rule| matches!(rule, Rule::MaxMoves(_)))
+            {
+                assert!(level.system.solve().len() <= *moves, "{}", level.title);
+            }
+        }
+        assert_eq!(levels, campaign());
+    }
+    #[test]
+    fn parses_levels() {
+        let levels = parse_levels(
+            "# A comment\n\
+             level No Swapping\n\
+             note The top left coefficient is 0.\n\
+             rule no-swaps\n\
+             rule max-moves 4\n\
+             0 2 | 6\n\
+             1 1 | 4\n\
+             \n\
+             level Second\n\
+             1 | 2\n",
+        )
+        .unwrap();
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[0].title, "No Swapping");
+        assert_eq!(levels[0].notes, ["The top left coefficient is 0."]);
+        assert_eq!(levels[0].rules, [Rule::NoSwaps, Rule::MaxMoves(4)]);
+        assert_eq!(levels[0].system.equations[1].solution, 4.0);
+        assert_eq!(levels[1].system.size(), 1);
+    }
+    #[test]
+    fn reports_bad_levels() {
+        assert!(parse_levels("1 | 2").is_err());
+        assert!(parse_levels("level Bad Rule\nrule no-adding\n1 | 2").is_err());
+        assert!(parse_levels("level Bad Moves\nrule max-moves many\n1 | 2").is_err());
+        let error = parse_levels("level Not Square\n1 2 | 3").unwrap_err();
+        assert!(error.contains("Not Square"), "{}", error);
+    }
+    #[test]
+    fn checks_rules() {
+        let swap = RowOperation::SwitchRows(0, 1);
+        let scale = RowOperation::MakeCoefficient1(0, 0);
+        let add = RowOperation::MakeCoefficient0WithRow(1, 0, 0);
+        assert!(Rule::NoSwaps.check(swap, 0).is_err());
+        assert!(Rule::NoSwaps.check(scale, 0).is_ok());
+        assert!(Rule::NoScaling.check(scale, 0).is_err());
+        assert!(Rule::NoScaling.check(add, 0).is_ok());
+        assert!(Rule::MaxMoves(2).check(add, 1).is_ok());
+        assert!(Rule::MaxMoves(2).check(add, 2).is_err());
+    }
+}
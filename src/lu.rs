@@ -0,0 +1,370 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!LU decomposition mode. The player clears the coefficients below the diagonal only by adding
+//!multiples of the row with the pivot for that column, and each multiplier goes into L, so once
+//!the coefficients are upper triangular they're U and PA = LU. Rows can be switched as long as L
+//!doesn't use them yet, and the switches are kept in the permutation matrix P.
+use super::*;
+///The decomposition of the coefficients the player started with so far
+#[derive(Clone, Debug, PartialEq)]
+pub struct LuDecomposition {
+    ///The coefficients before any operations, A
+    pub start: Matrix,
+    ///Which row of A each row of the board came from
+    pub permutation: Vec<usize>,
+    ///L, which starts as the identity matrix
+    pub lower: Matrix,
+}
+impl LuDecomposition {
+    pub fn new(system: &System) -> Self {
+        Self {
+            start: system.coefficient_matrix(),
+            permutation: (0..system.size()).collect(),
+            lower: Matrix::identity(system.size()),
+        }
+    }
+    pub fn permutation_matrix(&self) -> Matrix {
+        let mut matrix = Matrix::identity(self.permutation.len());
+        for (row, from) in matrix.rows.iter_mut().zip(self.permutation.iter()) {
+            row.iter_mut()
+                .enumerate()
+                .for_each(|(i, number)| *number = if i == *from { 1.0 } else { 0.0 });
+        }
+        matrix
+    }
+    ///Checks if `operation` can be done to `system` while keeping track of L and P, and says why
+    ///not if it can't.
+    pub fn check(&self, operation: RowOperation, system: &System) -> Result<(), String> {
+        if system.is_upper_triangular() {
+            return Err(tr(
+                "The coefficients are already upper triangular. Click \"New\" for another system.",
+            )
+            .to_string());
+        }
+        match operation {
+            RowOperation::MakeCoefficient1(..) => Err(tr(
+                "LU decomposition doesn't scale rows. The pivots stay where they are in U.",
+            )
+            .to_string()),
+            RowOperation::MakeCoefficient0WithRow(equation, coefficient, with) => {
+                if coefficient != with {
+                    Err(tr_format(
+                        "Clear column {} with row {}, since that's the row with its pivot.",
+                        &[&(coefficient + 1), &(coefficient + 1)],
+                    ))
+                } else if equation < with {
+                    Err(tr(
+                        "Only the coefficients below the diagonal are cleared in LU decomposition.",
+                    )
+                    .to_string())
+                } else if system.equations[with].coefficients[..with]
+                    .iter()
+                    .any(|coefficient| *coefficient != 0.0)
+                {
+                    Err(tr_format(
+                        "Row {} has to be cleared left of the diagonal before it can be used.",
+                        &[&(with + 1)],
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            RowOperation::SwitchRows(a, b) => {
+                let (top, bottom) = (a.min(b), a.max(b));
+                //L only stays lower triangular if neither row has been used to clear anything and
+                //the bottom row hasn't been cleared in any column the top row can't have.
+                let used = |column: usize| {
+                    self.lower.rows[column + 1..]
+                        .iter()
+                        .any(|row| row[column] != 0.0)
+                };
+                if used(top)
+                    || used(bottom)
+                    || self.lower.rows[bottom][top..bottom]
+                        .iter()
+                        .any(|multiplier| *multiplier != 0.0)
+                {
+                    Err(tr("L already depends on those rows being where they are. Switch rows before clearing their columns.").to_string())
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+    ///Adds the operation's multiplier to L or its switch to P. `system` is from before the
+    ///operation.
+    pub fn record(&mut self, operation: RowOperation, system: &System) {
+        match operation {
+            RowOperation::MakeCoefficient1(..) => {}
+            RowOperation::MakeCoefficient0WithRow(equation, coefficient, with) => {
+                //Adding to the multiplier instead of replacing it handles a column that was
+                //cleared, filled back in, and cleared again.
+                self.lower.rows[equation][with] += system.equations[equation].coefficients
+                    [coefficient]
+                    / system.equations[with].coefficients[coefficient];
+            }
+            RowOperation::SwitchRows(a, b) => {
+                self.permutation.swap(a, b);
+                let (top, bottom) = (a.min(b), a.max(b));
+                for column in 0..top {
+                    let multiplier = self.lower.rows[top][column];
+                    self.lower.rows[top][column] = self.lower.rows[bottom][column];
+                    self.lower.rows[bottom][column] = multiplier;
+                }
+            }
+        }
+    }
+    ///The next operation for decomposing `system`, going column by column, or why there isn't
+    ///one
+    pub fn hint(&self, system: &System) -> Result<RowOperation, String> {
+        for column in 0..system.size() {
+            let mut below = (column + 1..system.size())
+                .filter(|row| system.equations[*row].coefficients[column] != 0.0);
+            if system.equations[column].coefficients[column] != 0.0 {
+                if let Some(row) = below.next() {
+                    return Ok(RowOperation::MakeCoefficient0WithRow(row, column, column));
+                }
+            } else if let Some(row) = below.next() {
+                let operation = RowOperation::SwitchRows(column, row);
+                //If these rows can't be switched, nothing can be done.
+                return match self.check(operation, system) {
+                    Ok(()) => Ok(operation),
+                    Err(_) => Err(tr("This decomposition needs a row switch that's no longer allowed. Click \"New\" to start over.").to_string()),
+                };
+            }
+        }
+        Err(tr("The coefficients are already upper triangular.").to_string())
+    }
+    ///The largest difference between an entry of PA and the same entry of LU
+    pub fn error(&self, upper: &Matrix) -> f64 {
+        let permuted = self.permutation_matrix() * self.start.clone();
+        let product = self.lower.clone() * upper.clone();
+        permuted
+            .rows
+            .iter()
+            .flatten()
+            .zip(product.rows.iter().flatten())
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max)
+    }
+}
+impl System {
+    ///Checks if every coefficient below the diagonal is 0.
+    pub fn is_upper_triangular(&self) -> bool {
+        self.equations.iter().enumerate().all(|(i, equation)| {
+            equation.coefficients[..i]
+                .iter()
+                .all(|coefficient| *coefficient == 0.0)
+        })
+    }
+}
+///How big the LU panel needs to be for a system of `size` equations
+pub fn lu_panel_size(size: usize) -> (i32, i32) {
+    let (width, height) = titled_matrix_size(size);
+    ((2.0 * width + 10.0) as i32, (2.0 * height + 40.0) as i32)
+}
+pub fn build_lu_panel(size: usize) -> DrawingArea {
+    let (width, height) = lu_panel_size(size);
+    DrawingArea::builder()
+        .width_request(width)
+        .height_request(height)
+        .margin_top(10)
+        .margin_bottom(10)
+        .margin_end(10)
+        .visible(false)
+        .build()
+}
+///Draws L and U side by side with P below them, and whether PA = LU once U is done, in the
+///current color.
+pub fn draw_lu(
+    context: &gtk4::cairo::Context,
+    lu: &LuDecomposition,
+    system: &System,
+    number_format: NumberFormat,
+) {
+    let upper = system.coefficient_matrix();
+    let (width, _) = titled_matrix_size(system.size());
+    draw_titled_matrix(context, 10.0, 10.0, "L", &lu.lower, number_format);
+    let y = draw_titled_matrix(context, 10.0 + width, 10.0, "U", &upper, number_format);
+    let y = draw_titled_matrix(
+        context,
+        10.0,
+        y,
+        "P",
+        &lu.permutation_matrix(),
+        number_format,
+    );
+    let result = if system.is_upper_triangular() {
+        let error = lu.error(&upper);
+        if error <= 1e-9 * upper.rows.iter().flatten().fold(1.0, |a, b| b.abs().max(a)) {
+            "PA = LU ✓".to_string()
+        } else {
+            tr_format("PA and LU differ by up to {}", &[&format_float(error)])
+        }
+    } else {
+        tr("U isn't upper triangular yet.").to_string()
+    };
+    context.move_to(10.0, y + 14.0);
+    context.show_text(&result).unwrap();
+}
+impl Game {
+    ///Puts a system with a unique solution on the board and starts decomposing it.
+    pub fn start_lu(&self) {
+        let system = loop {
+            let system = System::random(
+                self.size.get(),
+                self.difficulty.get(),
+                &mut *self.rng.borrow_mut(),
+            );
+            if system.has_unique_solution() {
+                break system;
+            }
+        };
+        self.set_system(system, None);
+        *self.lu.borrow_mut() = Some(LuDecomposition::new(&self.system.borrow()));
+        let (width, height) = lu_panel_size(self.system.borrow().size());
+        self.lu_panel.set_width_request(width);
+        self.lu_panel.set_height_request(height);
+        self.lu_panel.set_visible(true);
+        self.lu_panel.queue_draw();
+        self.update_lu_hud();
+    }
+    fn update_lu_hud(&self) {
+        let lu = self.lu.borrow();
+        let Some(lu) = lu.as_ref() else {
+            return;
+        };
+        let text = if self.system.borrow().is_upper_triangular() {
+            let swaps = if lu
+                .permutation
+                .iter()
+                .enumerate()
+                .all(|(i, from)| i == *from)
+            {
+                "No rows were switched, so P is the identity matrix and A = LU."
+            } else {
+                "P records the rows that were switched."
+            };
+            tr_format(
+                "The coefficients are upper triangular, so they're U. {}",
+                &[&tr(swaps)],
+            )
+        } else {
+            tr("LU decomposition: clear the coefficients below the diagonal by dragging from the row with each column's pivot. Each multiplier goes into L. If a pivot is 0, switch rows first.").to_string()
+        };
+        self.hud.set_label(&text);
+        self.hud.set_visible(true);
+    }
+    ///Checks if `operation` is allowed while decomposing.
+    pub(crate) fn check_lu(&self, operation: RowOperation) -> Result<(), String> {
+        match self.lu.borrow().as_ref() {
+            Some(lu) => lu.check(operation, &self.system.borrow()),
+            None => Ok(()),
+        }
+    }
+    ///Keeps track of L and P before an operation is done. Operations from the solver window that
+    ///LU decomposition doesn't allow stop the decomposition.
+    pub(crate) fn record_lu(&self, operation: RowOperation) {
+        let mut lu = self.lu.borrow_mut();
+        let Some(decomposition) = lu.as_mut() else {
+            return;
+        };
+        let system = self.system.borrow();
+        match decomposition.check(operation, &system) {
+            Ok(()) => decomposition.record(operation, &system),
+            Err(_) => {
+                *lu = None;
+                self.lu_panel.set_visible(false);
+                self.hud.set_visible(false);
+                self.show_toast(tr("That operation isn't part of LU decomposition, so L and U aren't being kept track of anymore."));
+            }
+        }
+    }
+    ///Shows the new L and U after an operation.
+    pub(crate) fn update_lu(&self) {
+        self.lu_panel.queue_draw();
+        self.update_lu_hud();
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn system(rows: &[&[f64]]) -> System {
+        System::new(
+            rows.iter()
+                .map(|row| Equation::new(row.to_vec(), 0.0))
+                .collect(),
+        )
+    }
+    ///Checks and does each operation, keeping track of the decomposition.
+    fn decompose(lu: &mut LuDecomposition, system: &mut System, operations: &[RowOperation]) {
+        for operation in operations {
+            assert_eq!(lu.check(*operation, system), Ok(()), "{:?}", operation);
+            lu.record(*operation, system);
+            system.apply(*operation);
+        }
+    }
+    #[test]
+    fn following_hints_gives_pa_equals_lu() {
+        let mut system = system(&[&[0.0, 2.0, 1.0], &[1.0, 1.0, 1.0], &[2.0, 1.0, 3.0]]);
+        let mut lu = LuDecomposition::new(&system);
+        while let Ok(hint) = lu.hint(&system) {
+            decompose(&mut lu, &mut system, &[hint]);
+        }
+        assert!(system.is_upper_triangular());
+        assert_eq!(
+            lu.hint(&system),
+            Err("The coefficients are already upper triangular.".to_string())
+        );
+        assert_eq!(lu.permutation, [1, 0, 2]);
+        assert!(lu.error(&system.coefficient_matrix()) < 1e-12);
+        assert_eq!(lu.lower.rows[2][..2], [2.0, -0.5]);
+    }
+    #[test]
+    fn keeps_to_lu_operations() {
+        let system = system(&[&[2.0, 1.0], &[4.0, 3.0]]);
+        let lu = LuDecomposition::new(&system);
+        assert!(
+            lu.check(RowOperation::MakeCoefficient1(0, 0), &system)
+                .is_err()
+        );
+        assert!(
+            lu.check(RowOperation::MakeCoefficient0WithRow(1, 0, 1), &system)
+                .is_err()
+        );
+        assert!(
+            lu.check(RowOperation::MakeCoefficient0WithRow(0, 1, 1), &system)
+                .is_err()
+        );
+        assert_eq!(
+            lu.check(RowOperation::MakeCoefficient0WithRow(1, 0, 0), &system),
+            Ok(())
+        );
+        assert_eq!(lu.check(RowOperation::SwitchRows(0, 1), &system), Ok(()));
+    }
+    #[test]
+    fn says_when_a_needed_switch_is_blocked() {
+        let mut system = system(&[
+            &[1.0, 1.0, 1.0, 1.0],
+            &[1.0, 1.0, 2.0, 3.0],
+            &[1.0, 1.0, 2.0, 1.0],
+            &[1.0, 2.0, 3.0, 4.0],
+        ]);
+        let mut lu = LuDecomposition::new(&system);
+        //Clearing column 3 of the last row with row 3 before column 2 has a pivot means L
+        //depends on the last row staying where it is.
+        decompose(
+            &mut lu,
+            &mut system,
+            &[
+                RowOperation::MakeCoefficient0WithRow(1, 0, 0),
+                RowOperation::MakeCoefficient0WithRow(2, 0, 0),
+                RowOperation::MakeCoefficient0WithRow(3, 0, 0),
+                RowOperation::MakeCoefficient0WithRow(3, 2, 2),
+            ],
+        );
+        assert!(lu.check(RowOperation::SwitchRows(1, 3), &system).is_err());
+        let reason = lu.hint(&system).unwrap_err();
+        assert!(reason.contains("no longer allowed"), "{}", reason);
+    }
+}
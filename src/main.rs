@@ -2,16 +2,75 @@
 // Copyright 2025 UxuginPython
 use gtk4::prelude::*;
 use gtk4::{
-    Application, ApplicationWindow, Button, DrawingArea, GestureClick, GestureDrag, Label,
-    Notebook, Orientation, glib,
+    Application, ApplicationWindow, Button, ButtonsType, DrawingArea, DropDown, FileChooserAction,
+    FileChooserNative, GestureClick, GestureDrag, Label, MessageDialog, MessageType, Notebook,
+    Orientation, ResponseType, ToggleButton, glib,
 };
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use std::cell::{Cell, RefCell};
+use std::fmt::Display;
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::str::FromStr;
+mod achievements;
+use achievements::*;
 mod algebra;
 use algebra::*;
-const SYSTEM_SIZE: usize = 4;
+mod complex;
+mod cramer;
+use cramer::*;
+mod daily;
+mod elementary;
+use elementary::*;
+mod export;
+use export::*;
+mod field;
+use field::*;
+mod latex;
+use latex::*;
+mod levels;
+use levels::*;
+mod lu;
+use lu::*;
+mod mistakes;
+use mistakes::*;
+mod modes;
+use modes::*;
+mod modular;
+mod palette;
+use palette::*;
+mod puzzle;
+use puzzle::*;
+mod solve_command;
+use solve_command::*;
+mod game;
+use game::*;
+mod geometry;
+use geometry::*;
+mod i18n;
+use i18n::*;
+mod solver;
+use solver::*;
+mod settings;
+use settings::*;
+mod stats;
+use stats::*;
+mod symbolic;
+use symbolic::*;
+mod tutorial;
+mod watch;
+use watch::*;
+const DEFAULT_SYSTEM_SIZE: usize = 4;
+const MAX_SYSTEM_SIZE: usize = 8;
+///The smallest size of a box on the board, which is also its size in exported images
 const BOX_SIZE: f64 = 50.0;
+///How big each box on the board is for a system of `size` equations to fill an area `width` by
+///`height` without stretching
+fn box_size(width: f64, height: f64, size: usize) -> f64 {
+    (width / (size + 2) as f64).min(height / size as f64)
+}
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum CanvasItem {
     Circle(usize),
@@ -19,26 +78,35 @@ enum CanvasItem {
     Solution(usize),
 }
 impl CanvasItem {
-    fn from_coordinates(x: f64, y: f64) -> Self {
-        let equation = (y / BOX_SIZE) as usize; //rounds down
-        if x < BOX_SIZE {
-            Self::Circle(equation)
-        } else if x < (SYSTEM_SIZE + 1) as f64 * BOX_SIZE {
-            Self::Coefficient(equation, (x / BOX_SIZE) as usize - 1)
+    ///Returns `None` if the coordinates are outside the board for a system of `size` equations
+    ///with boxes `box_size` across.
+    fn from_coordinates(x: f64, y: f64, size: usize, box_size: f64) -> Option<Self> {
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+        let equation = (y / box_size) as usize; //rounds down
+        if equation >= size {
+            None
+        } else if x < box_size {
+            Some(Self::Circle(equation))
+        } else if x < (size + 1) as f64 * box_size {
+            Some(Self::Coefficient(equation, (x / box_size) as usize - 1))
+        } else if x < (size + 2) as f64 * box_size {
+            Some(Self::Solution(equation))
         } else {
-            Self::Solution(equation)
+            None
         }
     }
-    fn get_center(&self) -> (f64, f64) {
+    fn get_center(&self, size: usize, box_size: f64) -> (f64, f64) {
         match *self {
-            Self::Circle(equation) => (BOX_SIZE / 2.0, BOX_SIZE * equation as f64 + BOX_SIZE / 2.0),
+            Self::Circle(equation) => (box_size / 2.0, box_size * equation as f64 + box_size / 2.0),
             Self::Coefficient(equation, coefficient) => (
-                BOX_SIZE * coefficient as f64 + BOX_SIZE * 1.5,
-                BOX_SIZE * equation as f64 + BOX_SIZE / 2.0,
+                box_size * coefficient as f64 + box_size * 1.5,
+                box_size * equation as f64 + box_size / 2.0,
             ),
             Self::Solution(equation) => (
-                SYSTEM_SIZE as f64 * BOX_SIZE + BOX_SIZE * 1.5,
-                BOX_SIZE * equation as f64 + BOX_SIZE / 2.0,
+                size as f64 * box_size + box_size * 1.5,
+                box_size * equation as f64 + box_size / 2.0,
             ),
         }
     }
@@ -52,22 +120,22 @@ fn draw_x(context: &gtk4::cairo::Context, x: f64, y: f64) {
     context.stroke().unwrap();
 }
 #[allow(dead_code)]
-fn plot_centers(context: &gtk4::cairo::Context) {
+fn plot_centers(context: &gtk4::cairo::Context, size: usize, box_size: f64) {
     context.set_source_rgb(1.0, 0.0, 0.0);
-    for i in 0..SYSTEM_SIZE {
-        let (x, y) = CanvasItem::Circle(i).get_center();
+    for i in 0..size {
+        let (x, y) = CanvasItem::Circle(i).get_center(size, box_size);
         draw_x(context, x, y);
     }
     context.set_source_rgb(0.0, 0.5, 0.0);
-    for i in 0..SYSTEM_SIZE {
-        for j in 0..SYSTEM_SIZE {
-            let (x, y) = CanvasItem::Coefficient(i, j).get_center();
+    for i in 0..size {
+        for j in 0..size {
+            let (x, y) = CanvasItem::Coefficient(i, j).get_center(size, box_size);
             draw_x(context, x, y);
         }
     }
     context.set_source_rgb(0.0, 0.0, 1.0);
-    for i in 0..SYSTEM_SIZE {
-        let (x, y) = CanvasItem::Solution(i).get_center();
+    for i in 0..size {
+        let (x, y) = CanvasItem::Solution(i).get_center(size, box_size);
         draw_x(context, x, y);
     }
 }
@@ -76,11 +144,227 @@ fn draw_text_centered(context: &gtk4::cairo::Context, x: f64, y: f64, text: &str
     context.move_to(x - extents.width() / 2.0, y + extents.height() / 2.0);
     context.show_text(text).unwrap();
 }
+///Fills a panel that only makes sense for real numbers with a note saying so.
+fn draw_real_only(context: &gtk4::cairo::Context, width: f64, height: f64) {
+    context.set_source_rgb(1.0, 1.0, 1.0);
+    context.paint().unwrap();
+    context.set_source_rgb(0.0, 0.0, 0.0);
+    context.set_font_size(14.0);
+    draw_text_centered(
+        context,
+        width / 2.0,
+        height / 2.0,
+        tr("Only for real numbers"),
+    );
+}
+///Draws a number in a box `box_size` across, shrinking it if it's too wide to fit.
+fn draw_number(context: &gtk4::cairo::Context, x: f64, y: f64, text: &str, box_size: f64) {
+    //The font grows with the box.
+    let font_size = 18.0 * box_size / BOX_SIZE;
+    context.set_font_size(font_size);
+    let width = context.text_extents(text).unwrap().width();
+    if width > box_size * 0.9 {
+        context.set_font_size(font_size * box_size * 0.9 / width);
+    }
+    draw_text_centered(context, x, y, text);
+}
+///How the board is drawn
+#[derive(Clone, Copy, Debug)]
+struct BoardStyle {
+    ///How big each box is
+    box_size: f64,
+    number_format: NumberFormat,
+    palette: Palette,
+    ///Whether to color the pivots, the coefficients left to eliminate, and the cleared 0s
+    structure: bool,
+}
+///Draws the board from the top left corner. `highlights` are filled in behind, and `mistakes` are
+///outlined with dashes so that they stand out without relying on color.
+fn draw_board<F: Field>(
+    context: &gtk4::cairo::Context,
+    system: &System<F>,
+    style: BoardStyle,
+    selected_row: Option<usize>,
+    hint: Option<RowOperation>,
+    highlights: &[CanvasItem],
+    mistakes: &[CanvasItem],
+) {
+    let BoardStyle {
+        box_size,
+        number_format,
+        palette,
+        structure,
+    } = style;
+    let size = system.size();
+    let line_width = palette.line_width * box_size / BOX_SIZE;
+    context.set_line_width(line_width);
+    let structure = structure.then(|| system.structure());
+    if let Some(structure) = &structure {
+        let fill_box = |item: CanvasItem, color: Color, alpha: f64| {
+            let (x, y) = item.get_center(size, box_size);
+            context.set_source_rgba(color.0, color.1, color.2, alpha);
+            context.rectangle(x - box_size / 2.0, y - box_size / 2.0, box_size, box_size);
+            context.fill().unwrap();
+        };
+        for (i, row) in structure.cells.iter().enumerate() {
+            for (j, status) in row.iter().enumerate() {
+                if *status == CellStatus::ToEliminate {
+                    fill_box(CanvasItem::Coefficient(i, j), palette.remaining, 0.25);
+                }
+            }
+        }
+        //Pivots are outlined too so that they can be told apart without color.
+        for (i, column) in structure.pivot_columns.iter().enumerate() {
+            if let Some(j) = *column {
+                fill_box(CanvasItem::Coefficient(i, j), palette.pivot, 0.3);
+                let (x, y) = CanvasItem::Coefficient(i, j).get_center(size, box_size);
+                let half = box_size * 0.45;
+                set_color(context, palette.pivot);
+                context.rectangle(x - half, y - half, half * 2.0, half * 2.0);
+                context.stroke().unwrap();
+            }
+        }
+    }
+    set_color(context, palette.highlight);
+    for item in highlights {
+        let (x, y) = item.get_center(size, box_size);
+        context.rectangle(x - box_size / 2.0, y - box_size / 2.0, box_size, box_size);
+        context.fill().unwrap();
+    }
+    set_color(context, palette.error);
+    context.set_line_width(line_width * 2.0);
+    context.set_dash(&[box_size / 10.0], 0.0);
+    for item in mistakes {
+        let (x, y) = item.get_center(size, box_size);
+        let half = box_size * 0.45;
+        context.rectangle(x - half, y - half, half * 2.0, half * 2.0);
+        context.stroke().unwrap();
+    }
+    context.set_dash(&[], 0.0);
+    context.set_line_width(line_width);
+    set_color(context, palette.foreground);
+    context.line_to(box_size * 1.5, 0.0);
+    context.line_to(box_size, 0.0);
+    context.line_to(box_size, box_size * size as f64);
+    context.line_to(box_size * 1.5, box_size * size as f64);
+    context.stroke().unwrap();
+    context.line_to((size + 2) as f64 * box_size - 0.5 * box_size, 0.0);
+    context.line_to((size + 2) as f64 * box_size, 0.0);
+    context.line_to((size + 2) as f64 * box_size, box_size * size as f64);
+    context.line_to(
+        (size + 2) as f64 * box_size - 0.5 * box_size,
+        box_size * size as f64,
+    );
+    context.stroke().unwrap();
+    context.line_to((size + 1) as f64 * box_size, 0.0);
+    context.line_to((size + 1) as f64 * box_size, box_size * size as f64);
+    context.stroke().unwrap();
+    for i in 0..size {
+        for j in 0..size {
+            let (x, y) = CanvasItem::Coefficient(i, j).get_center(size, box_size);
+            //Cleared 0s fade into the background.
+            if let Some(structure) = &structure
+                && structure.cells[i][j] == CellStatus::Cleared
+            {
+                let color = palette.foreground;
+                context.set_source_rgba(color.0, color.1, color.2, 0.4);
+            } else {
+                set_color(context, palette.foreground);
+            }
+            draw_number(
+                context,
+                x,
+                y,
+                &system.equations[i].coefficients[j].format(number_format),
+                box_size,
+            );
+        }
+    }
+    set_color(context, palette.foreground);
+    for i in 0..size {
+        let (x, y) = CanvasItem::Solution(i).get_center(size, box_size);
+        draw_number(
+            context,
+            x,
+            y,
+            &system.equations[i].solution.format(number_format),
+            box_size,
+        );
+    }
+    if let Some(i) = selected_row {
+        set_color(context, palette.selection);
+        context.arc(
+            0.5 * box_size,
+            i as f64 * box_size + 0.5 * box_size,
+            0.25 * box_size,
+            0.0,
+            std::f64::consts::PI * 2.0,
+        );
+        context.fill().unwrap();
+        set_color(context, palette.foreground);
+    }
+    context.move_to(0.75 * box_size, 0.5 * box_size);
+    for i in 0..size {
+        context.arc(
+            0.5 * box_size,
+            i as f64 * box_size + 0.5 * box_size,
+            0.25 * box_size,
+            0.0,
+            std::f64::consts::PI * 2.0,
+        );
+        context.stroke().unwrap();
+    }
+    set_color(context, palette.hint);
+    match hint {
+        //Drawn the same way as the drag that does it
+        Some(RowOperation::SwitchRows(a, b)) => {
+            let (start_x, start_y) = CanvasItem::Circle(a).get_center(size, box_size);
+            let (end_x, end_y) = CanvasItem::Circle(b).get_center(size, box_size);
+            context.line_to(start_x, start_y);
+            context.line_to(end_x, end_y);
+            context.stroke().unwrap();
+        }
+        Some(RowOperation::MakeCoefficient0WithRow(equation, coefficient, with)) => {
+            let (start_x, start_y) = CanvasItem::Circle(with).get_center(size, box_size);
+            let (end_x, end_y) =
+                CanvasItem::Coefficient(equation, coefficient).get_center(size, box_size);
+            context.line_to(start_x, start_y);
+            context.line_to(end_x, end_y);
+            context.stroke().unwrap();
+            //Circles the pivot it's eliminated with
+            set_color(context, palette.pivot);
+            let (x, y) = CanvasItem::Coefficient(with, coefficient).get_center(size, box_size);
+            context.new_sub_path();
+            context.arc(x, y, 0.45 * box_size, 0.0, std::f64::consts::PI * 2.0);
+            context.stroke().unwrap();
+        }
+        //Circles the coefficient to click
+        Some(RowOperation::MakeCoefficient1(equation, coefficient)) => {
+            let (x, y) = CanvasItem::Coefficient(equation, coefficient).get_center(size, box_size);
+            context.new_sub_path();
+            context.arc(x, y, 0.45 * box_size, 0.0, std::f64::consts::PI * 2.0);
+            context.stroke().unwrap();
+        }
+        None => {}
+    }
+    set_color(context, palette.foreground);
+    context.set_line_width(1.0);
+}
+///Formats a float with the locale's decimal separator.
 fn format_float(float: f64) -> String {
+    localize_decimal(format_float_with_point(float))
+}
+///Formats a float with a decimal point whatever the locale, for files other programs read
+fn format_float_with_point(float: f64) -> String {
     //Format -0.0 as 0
     if float == 0.0 {
         return "0".into();
     }
+    //Rounding error from pivoting can leave numbers that would show as 0.000 or be too wide for
+    //their box.
+    if float.abs() < 1e-3 || float.abs() >= 1e6 {
+        return format!("{:.1e}", float);
+    }
     let normal = format!("{}", float);
     let rounded = format!("{:.3}", float);
     if normal.len() <= rounded.len() {
@@ -89,247 +373,1115 @@ fn format_float(float: f64) -> String {
         rounded
     }
 }
+///How numbers are shown on the board
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum NumberFormat {
+    #[default]
+    Decimal,
+    ///Fractions with small denominators, falling back to decimals for anything else
+    Fraction,
+}
+impl NumberFormat {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Decimal => "Decimal",
+            Self::Fraction => "Fraction",
+        }
+    }
+}
+impl FromStr for NumberFormat {
+    type Err = String;
+    fn from_str(string: &str) -> Result<Self, String> {
+        match string.to_ascii_lowercase().as_str() {
+            "decimal" => Ok(Self::Decimal),
+            "fraction" => Ok(Self::Fraction),
+            _ => Err(format!(
+                "unknown number format {:?}, expected decimal or fraction",
+                string
+            )),
+        }
+    }
+}
+fn format_number(float: f64, format: NumberFormat) -> String {
+    localize_decimal(format_number_with_point(float, format))
+}
+///Like `format_number` but with a decimal point whatever the locale
+fn format_number_with_point(float: f64, format: NumberFormat) -> String {
+    match (format, approximate_fraction(float)) {
+        (NumberFormat::Fraction, Some((numerator, 1))) => numerator.to_string(),
+        (NumberFormat::Fraction, Some((numerator, denominator))) => {
+            format!("{}/{}", numerator, denominator)
+        }
+        _ => format_float_with_point(float),
+    }
+}
+///Formats a complex number as a+bi, leaving out a part that's 0. A fraction's denominator goes
+///after the i so that 3/2 i doesn't look like 3/(2i).
+fn format_complex(complex: Complex, format: NumberFormat) -> String {
+    let real = format_number(complex.re, format);
+    if complex.im == 0.0 {
+        return real;
+    }
+    let imaginary = match complex.im {
+        1.0 => "i".to_string(),
+        -1.0 => "-i".to_string(),
+        im => match format_number(im, format).split_once('/') {
+            Some((numerator, denominator)) => format!("{}i/{}", numerator, denominator),
+            None => format!("{}i", format_number(im, format)),
+        },
+    };
+    if complex.re == 0.0 {
+        imaginary
+    } else if complex.im < 0.0 {
+        format!("{}{}", real, imaginary)
+    } else {
+        format!("{}+{}", real, imaginary)
+    }
+}
+///Describes the operation in plain text as it would be applied to `system`.
+fn describe_operation(
+    system: &System,
+    operation: RowOperation,
+    number_format: NumberFormat,
+) -> String {
+    write_operation(
+        system,
+        operation,
+        |float| format_number(float, number_format),
+        tr_format,
+    )
+}
+///Like `describe_operation` but in English with a decimal point whatever the locale, for output
+///other programs read
+fn describe_operation_with_point(
+    system: &System,
+    operation: RowOperation,
+    number_format: NumberFormat,
+) -> String {
+    write_operation(
+        system,
+        operation,
+        |float| format_number_with_point(float, number_format),
+        fill_template,
+    )
+}
+///Describes the operation with `format` for numbers and `fill` to fill in the templates.
+fn write_operation(
+    system: &System,
+    operation: RowOperation,
+    format: impl Fn(f64) -> String,
+    fill: impl Fn(&str, &[&dyn Display]) -> String,
+) -> String {
+    match operation {
+        RowOperation::SwitchRows(a, b) => fill("R{} ↔ R{}", &[&(a + 1), &(b + 1)]),
+        RowOperation::MakeCoefficient1(equation, coefficient) => {
+            let factor = 1.0 / system.equations[equation].coefficients[coefficient];
+            let factor = if factor == -1.0 {
+                "-".to_string()
+            } else {
+                format(factor)
+            };
+            fill("{}R{} → R{}", &[&factor, &(equation + 1), &(equation + 1)])
+        }
+        RowOperation::MakeCoefficient0WithRow(equation, coefficient, with) => {
+            let factor = system.equations[equation].coefficients[coefficient]
+                / system.equations[with].coefficients[coefficient];
+            let sign = if factor < 0.0 { '+' } else { '-' };
+            let factor = if factor.abs() == 1.0 {
+                String::new()
+            } else {
+                format(factor.abs())
+            };
+            fill(
+                "R{} {} {}R{} → R{}",
+                &[
+                    &(equation + 1),
+                    &sign,
+                    &factor,
+                    &(with + 1),
+                    &(equation + 1),
+                ],
+            )
+        }
+    }
+}
+///Puts a factor like 1+2i or k-1 in parentheses so that "times" applies to all of it.
+fn parenthesize_sum(factor: String) -> String {
+    let mut depth = 0;
+    let is_sum = factor.char_indices().any(|(i, character)| {
+        match character {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            //Not the sign in front or an exponent's sign
+            '+' | '-' => return i > 0 && depth == 0 && !factor[..i].ends_with('e'),
+            _ => {}
+        }
+        false
+    });
+    if is_sum {
+        format!("({})", factor)
+    } else {
+        factor
+    }
+}
+///Explains why the hint is the next thing to do, like "Column 2 below the pivot must be 0, so
+///subtract 3/2 times row 1 from row 3." Rows and columns are counted from 1.
+fn explain_hint<F: Field>(system: &System<F>, number_format: NumberFormat) -> Option<String> {
+    let (operation, column) = system.hint_with_column()?;
+    let explanation = match operation {
+        RowOperation::SwitchRows(row, pivot_row) => tr_format(
+            "Column {} needs a pivot in row {}, but that coefficient is 0. Row {} has a coefficient that isn't 0 there, so switch rows {} and {}.",
+            &[
+                &(column + 1),
+                &(row + 1),
+                &(pivot_row + 1),
+                &(row + 1),
+                &(pivot_row + 1),
+            ],
+        ),
+        RowOperation::MakeCoefficient1(row, column) => tr_format(
+            "The pivot in column {} must be 1, so divide row {} by {}.",
+            &[
+                &(column + 1),
+                &(row + 1),
+                &system.equations[row].coefficients[column].format(number_format),
+            ],
+        ),
+        RowOperation::MakeCoefficient0WithRow(equation, column, with) => {
+            let factor = system.equations[equation].coefficients[column]
+                / system.equations[with].coefficients[column];
+            let adding = factor.is_negative();
+            let factor = if adding { -factor } else { factor };
+            let side = tr(if equation > with { "below" } else { "above" });
+            if factor.is_one() {
+                tr_format(
+                    if adding {
+                        "Column {} {} the pivot must be 0, so add row {} to row {}."
+                    } else {
+                        "Column {} {} the pivot must be 0, so subtract row {} from row {}."
+                    },
+                    &[&(column + 1), &side, &(with + 1), &(equation + 1)],
+                )
+            } else {
+                tr_format(
+                    if adding {
+                        "Column {} {} the pivot must be 0, so add {} times row {} to row {}."
+                    } else {
+                        "Column {} {} the pivot must be 0, so subtract {} times row {} from row {}."
+                    },
+                    &[
+                        &(column + 1),
+                        &side,
+                        &parenthesize_sum(factor.format(number_format)),
+                        &(with + 1),
+                        &(equation + 1),
+                    ],
+                )
+            }
+        }
+    };
+    Some(explanation)
+}
+///Finds a fraction with a reasonably small denominator that is equal to the float apart from
+///rounding error. The sign is always on the numerator.
+fn approximate_fraction(float: f64) -> Option<(i64, i64)> {
+    const MAX_DENOMINATOR: i64 = 10000;
+    if !float.is_finite() || float.abs() > 1e12 {
+        return None;
+    }
+    let target = float.abs();
+    //Continued fraction convergents
+    let (mut previous_numerator, mut numerator) = (0, 1);
+    let (mut previous_denominator, mut denominator) = (1, 0);
+    let mut remainder = target;
+    loop {
+        let whole = remainder.floor();
+        let next_numerator = whole as i64 * numerator + previous_numerator;
+        let next_denominator = whole as i64 * denominator + previous_denominator;
+        if next_denominator > MAX_DENOMINATOR {
+            return None;
+        }
+        (previous_numerator, numerator) = (numerator, next_numerator);
+        (previous_denominator, denominator) = (denominator, next_denominator);
+        if (numerator as f64 / denominator as f64 - target).abs() <= 1e-9 * target {
+            break;
+        }
+        remainder = 1.0 / (remainder - whole);
+    }
+    if float < 0.0 {
+        Some((-numerator, denominator))
+    } else {
+        Some((numerator, denominator))
+    }
+}
+fn show_error(parent: Option<&gtk4::Window>, text: &str) {
+    let dialog = MessageDialog::builder()
+        .message_type(MessageType::Error)
+        .buttons(ButtonsType::Ok)
+        .text(text)
+        .modal(true)
+        .build();
+    dialog.set_transient_for(parent);
+    dialog.connect_response(|dialog, _| dialog.destroy());
+    dialog.present();
+}
+///A boolean option or one with a list of `(id, label)` options, the first being the default,
+///shown in a file dialog
+type FileChoice<'a> = (&'a str, &'a str, &'a [(&'a str, &'a str)]);
+///Asks where to save a file and calls `save` with the path if the user doesn't cancel. `save` can
+///get the values of `choices` from the dialog.
+fn choose_save_path(
+    parent: Option<&gtk4::Window>,
+    title: &str,
+    default_name: &str,
+    choices: &[FileChoice],
+    save: impl Fn(PathBuf, &FileChooserNative) + 'static,
+) {
+    let dialog = FileChooserNative::new(
+        Some(title),
+        parent,
+        FileChooserAction::Save,
+        Some(tr("Save")),
+        Some(tr("Cancel")),
+    );
+    dialog.set_current_name(default_name);
+    for (id, label, options) in choices {
+        dialog.add_choice(*id, *label, options);
+        if let Some((default, _)) = options.first() {
+            dialog.set_choice(id, default);
+        }
+    }
+    //The dialog isn't kept alive by GTK while it's open, so it holds onto itself until it gets a
+    //response.
+    let keep_alive = Rc::new(RefCell::new(Some(dialog.clone())));
+    dialog.connect_response(move |dialog, response| {
+        if response == ResponseType::Accept
+            && let Some(path) = dialog.file().and_then(|file| file.path())
+        {
+            save(path, dialog);
+        }
+        keep_alive.borrow_mut().take();
+    });
+    dialog.show();
+}
+///Settings from the command line for the window, starting from the saved ones
+#[derive(Clone, Debug)]
+struct LaunchOptions {
+    size: usize,
+    difficulty: Difficulty,
+    seed: Option<u64>,
+    ///A system from a puzzle file to start with instead of a random one
+    puzzle: Option<System>,
+    number_format: NumberFormat,
+    ///The saved settings, for the ones that can't be given on the command line
+    settings: Settings,
+}
+impl Default for LaunchOptions {
+    fn default() -> Self {
+        Self::from_settings(Settings::default())
+    }
+}
+impl LaunchOptions {
+    fn from_settings(settings: Settings) -> Self {
+        Self {
+            size: settings.size,
+            difficulty: settings.difficulty,
+            seed: None,
+            puzzle: None,
+            number_format: settings.number_format,
+            settings,
+        }
+    }
+    fn parse(options: &glib::VariantDict) -> Result<Self, String> {
+        let mut launch_options = Self::from_settings(Settings::load());
+        if let Ok(Some(size)) = options.lookup::<i32>("size") {
+            if !(1..=MAX_SYSTEM_SIZE as i32).contains(&size) {
+                return Err(format!(
+                    "the size must be between 1 and {}",
+                    MAX_SYSTEM_SIZE
+                ));
+            }
+            launch_options.size = size as usize;
+        }
+        if let Ok(Some(difficulty)) = options.lookup::<String>("difficulty") {
+            launch_options.difficulty = difficulty.parse()?;
+        }
+        if let Ok(Some(seed)) = options.lookup::<i64>("seed") {
+            launch_options.seed = Some(seed as u64);
+        }
+        if let Ok(Some(path)) = options.lookup::<PathBuf>("open") {
+            let text = std::fs::read_to_string(&path)
+                .map_err(|error| format!("could not read {}: {}", path.display(), error))?;
+            let system = parse_system(&text)
+                .map_err(|error| format!("could not open {}: {}", path.display(), error))?;
+            launch_options.size = system.size();
+            launch_options.puzzle = Some(system);
+        }
+        if let Ok(Some(number_format)) = options.lookup::<String>("number-format") {
+            launch_options.number_format = number_format.parse()?;
+        }
+        Ok(launch_options)
+    }
+    fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        }
+    }
+    fn first_system(&self, rng: &mut StdRng) -> System {
+        match &self.puzzle {
+            Some(system) => system.clone(),
+            None => System::random(self.size, self.difficulty, rng),
+        }
+    }
+}
+///Writes `count` random puzzle files named puzzle-1.txt, puzzle-2.txt, and so on.
+fn generate_puzzles(
+    directory: &Path,
+    count: usize,
+    launch_options: &LaunchOptions,
+) -> std::io::Result<()> {
+    std::fs::create_dir_all(directory)?;
+    let mut rng = launch_options.rng();
+    for i in 1..=count {
+        let system = System::random(launch_options.size, launch_options.difficulty, &mut rng);
+        std::fs::write(
+            directory.join(format!("puzzle-{}.txt", i)),
+            format_system(&system),
+        )?;
+    }
+    Ok(())
+}
+fn export_from_command_line(
+    path: &Path,
+    options: &glib::VariantDict,
+    launch_options: &LaunchOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let format = ImageFormat::from_path(path).ok_or("unknown image format")?;
+    let system = launch_options.first_system(&mut launch_options.rng());
+    //There aren't any moves yet from the command line, so the history is how the solver would
+    //solve it.
+    let operations = if options.contains("history") {
+        system.solve()
+    } else {
+        Vec::new()
+    };
+    let export_options = ExportOptions {
+        scale: options.lookup("scale").ok().flatten().unwrap_or(1.0),
+        hint: if options.contains("hint") {
+            system.hint()
+        } else {
+            None
+        },
+        history: if options.contains("history") {
+            Some((&system, operations.as_slice()))
+        } else {
+            None
+        },
+        number_format: launch_options.number_format,
+    };
+    export_image(path, format, &system, &export_options)
+}
+///Handles everything from the command line that doesn't need a window and saves the rest in
+///`launch_options` for `build_ui`. Returns -1 to keep going and open the window.
+fn handle_local_options(
+    options: &glib::VariantDict,
+    launch_options: &RefCell<LaunchOptions>,
+) -> i32 {
+    let parsed = match LaunchOptions::parse(options) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            eprintln!("{}", error);
+            return 1;
+        }
+    };
+    if let Ok(Some(count)) = options.lookup::<i32>("generate") {
+        let Ok(Some(directory)) = options.lookup::<PathBuf>("out") else {
+            eprintln!("--generate needs --out to know where to put the puzzles");
+            return 1;
+        };
+        if count < 1 {
+            eprintln!("--generate needs to make at least one puzzle");
+            return 1;
+        }
+        return match generate_puzzles(&directory, count as usize, &parsed) {
+            Ok(()) => 0,
+            Err(error) => {
+                eprintln!("Could not write to {}: {}", directory.display(), error);
+                1
+            }
+        };
+    }
+    if let Ok(Some(path)) = options.lookup::<PathBuf>("export") {
+        return match export_from_command_line(&path, options, &parsed) {
+            Ok(()) => 0,
+            Err(error) => {
+                eprintln!("Could not export {}: {}", path.display(), error);
+                1
+            }
+        };
+    }
+    *launch_options.borrow_mut() = parsed;
+    -1
+}
 fn main() -> glib::ExitCode {
+    //GTK would try to open the subcommand name as a file, so it's handled before GTK sees it.
+    if std::env::args_os().nth(1).is_some_and(|arg| arg == "solve") {
+        return run_solve_command(std::env::args_os().skip(2));
+    }
     let app = Application::builder()
         .application_id("com.uxugin.gauss_elim_game")
         .build();
-    app.connect_activate(build_ui);
+    app.add_main_option(
+        "size",
+        glib::Char::from(b'n'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::Int,
+        "Number of equations and unknowns in random systems",
+        Some("SIZE"),
+    );
+    app.add_main_option(
+        "difficulty",
+        glib::Char::from(b'd'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::String,
+        "Difficulty of random systems: easy, medium, or hard",
+        Some("DIFFICULTY"),
+    );
+    app.add_main_option(
+        "seed",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::Int64,
+        "Seed for random systems so that they're the same every time",
+        Some("SEED"),
+    );
+    app.add_main_option(
+        "open",
+        glib::Char::from(b'o'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::Filename,
+        "Start with the system in a puzzle file",
+        Some("FILE"),
+    );
+    app.add_main_option(
+        "number-format",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::String,
+        "How to show numbers: decimal or fraction",
+        Some("FORMAT"),
+    );
+    app.add_main_option(
+        "generate",
+        glib::Char::from(b'g'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::Int,
+        "Write this many random puzzle files instead of opening a window",
+        Some("COUNT"),
+    );
+    app.add_main_option(
+        "out",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::Filename,
+        "Directory for --generate to write puzzle files to",
+        Some("DIRECTORY"),
+    );
+    app.add_main_option(
+        "export",
+        glib::Char::from(b'e'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::Filename,
+        "Render the starting system to an SVG, PNG, or PDF file instead of opening a window",
+        Some("FILE"),
+    );
+    app.add_main_option(
+        "scale",
+        glib::Char::from(b's'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::Double,
+        "Scale the exported image by this much",
+        Some("SCALE"),
+    );
+    app.add_main_option(
+        "hint",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::None,
+        "Show the hint in the exported image",
+        None,
+    );
+    app.add_main_option(
+        "history",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::None,
+        "List the row operations that solve the system in the exported image",
+        None,
+    );
+    let launch_options = Rc::new(RefCell::new(LaunchOptions::default()));
+    let my_launch_options = Rc::clone(&launch_options);
+    app.connect_handle_local_options(move |_, options| {
+        handle_local_options(options, &my_launch_options)
+    });
+    app.connect_activate(move |app| build_ui(app, &launch_options.borrow()));
     app.run()
 }
-fn build_ui(app: &Application) {
-    let system = Rc::new(RefCell::new(System::random()));
-    let selected_row: Rc<Cell<Option<usize>>> = Rc::new(Cell::new(None));
-    let hint: Rc<Cell<Option<(usize, usize, usize)>>> = Rc::new(Cell::new(None));
+///The How to Play tab of the Help window, one paragraph at a time so that each can be translated
+///on its own
+const HOW_TO_PLAY: [&str; 13] = [
+    "To swap two rows, drag from the circle to the left of one to the circle of the other.",
+    "To scale a row to make a coefficient 1, click the coefficient.",
+    "To add a multiple of a row to another row to make a coefficient 0, drag from the row's circle to the coefficient.",
+    "Click \"Hint\" for a suggestion for what to do. A line shows what to drag, and a circle shows what to click. When the line is for adding a multiple of a row, the pivot it uses is circled too. An explanation of why appears below the board and when you hover over it. If a move doesn't help, what it changed is outlined with dashes until the next move.",
+    "Click \"Matrices\" to show the elementary matrix for the last operation next to the board, along with the product of all of them so far and that product times the starting coefficients. Doing a row operation is the same as multiplying by its elementary matrix, so once the system is solved, the product times the starting coefficients is the reduced row echelon form.",
+    "Click \"Graph\" to draw each equation as a line if there are 2 unknowns or a plane if there are 3. Drag to turn the planes. Row operations change the lines and planes, but they always meet at the same point, the answer.",
+    "Click \"Pivots\" to color the board: each column's pivot is outlined, the coefficients above and below it that still have to be made 0 are shaded in another color, and the 0s already cleared fade out.",
+    "Click \"Solver\" to watch the computer solve the system one operation at a time with different pivoting strategies and compare how much rounding error each one ends up with.",
+    "Click \"New\" to generate a new random system.",
+    "Click \"Levels\" to play the campaign, a series of handmade levels that go from 2×2 systems to systems with infinitely many solutions or none at all. Each level explains something new, and some have rules like no swapping rows. A level is complete when the system is in reduced row echelon form, and the next level unlocks.",
+    "Choose a mode next to \"New\" for a challenge. In Time Attack, solve as many systems as you can in 3 minutes. In Sprint, solve 5 systems as fast as you can. In Survival, keep solving systems without using more operations than the computer would; the game is over when you run out. Daily Puzzle is a new system every day, the same for everyone with the same size and difficulty. In LU Decomposition, clear only the coefficients below the diagonal using the row with each column's pivot. The multipliers build L next to the board, the coefficients become U, and any rows you switch are kept in P, so that PA = LU at the end. In Cramer's Rule, solve the system and then see it solved by Cramer's rule, with how much arithmetic each way took. In Mod 5, Mod 7, and Mod 11, every number is an integer mod that prime, so there are no fractions, and clicking a coefficient multiplies its row by the coefficient's inverse. In Complex Numbers, the coefficients are complex numbers a+bi, and the answer's real and imaginary parts are whole numbers. In Parameter k, the coefficients have a parameter k in them. Find which k give the system a unique solution: dividing by an expression in k assumes it isn't 0, and once the system is solved, what you assumed is compared with the exact answer. \"New\" starts the mode over.",
+    "Click \"Settings\" to choose the board size, difficulty, number format, animations, theme, colors, and the mode to start in. The colors can be high contrast or colorblind safe. They're saved for next time.",
+    "Click \"Export\" to save everything you've done to the system as a LaTeX document or the board as an SVG, PNG, or PDF image, depending on the file name.",
+];
+fn build_ui(app: &Application, launch_options: &LaunchOptions) {
+    launch_options.settings.apply();
+    let size = launch_options.size;
     let main_box = gtk4::Box::builder()
         .orientation(Orientation::Vertical)
         .build();
     let drawing_area = DrawingArea::builder()
-        .width_request(BOX_SIZE as i32 * (SYSTEM_SIZE + 2) as i32)
-        .height_request(BOX_SIZE as i32 * SYSTEM_SIZE as i32)
+        .width_request(BOX_SIZE as i32 * (size + 2) as i32)
+        .height_request(BOX_SIZE as i32 * size as i32)
+        .hexpand(true)
+        .vexpand(true)
         .margin_top(10)
         .margin_bottom(10)
         .margin_start(10)
         .margin_end(10)
         .build();
+    let game = Rc::new(Game::new(launch_options, drawing_area.clone()));
     let button_box = gtk4::Box::builder()
         .orientation(Orientation::Horizontal)
         .build();
-    let new_button = Button::builder().label("New").build();
-    let my_system = Rc::clone(&system);
-    let my_drawing_area = drawing_area.clone();
-    let my_selected_row = Rc::clone(&selected_row);
-    let my_hint = Rc::clone(&hint);
+    let new_button = Button::builder().label(tr("New")).build();
+    let my_game = Rc::clone(&game);
     new_button.connect_clicked(move |_| {
-        my_hint.set(None);
-        *my_system.borrow_mut() = System::random();
-        my_drawing_area.queue_draw();
+        my_game.start_run(my_game.mode.get());
+    });
+    let mode_names = GameMode::ALL.map(|mode| tr(mode.name()));
+    let mode_drop_down = DropDown::from_strings(&mode_names);
+    let my_game = Rc::clone(&game);
+    mode_drop_down.connect_selected_notify(move |drop_down| {
+        my_game.start_run(GameMode::ALL[drop_down.selected() as usize]);
     });
-    let hint_button = Button::builder().label("Hint").build();
-    let my_hint = Rc::clone(&hint);
-    let my_system = Rc::clone(&system);
-    let my_drawing_area = drawing_area.clone();
+    //A puzzle file from the command line is played instead of the saved mode.
+    if launch_options.puzzle.is_none()
+        && let Some(index) = GameMode::ALL
+            .iter()
+            .position(|mode| *mode == launch_options.settings.mode)
+    {
+        mode_drop_down.set_selected(index as u32);
+    }
+    let hint_button = Button::builder().label(tr("Hint")).build();
+    let my_game = Rc::clone(&game);
     hint_button.connect_clicked(move |_| {
-        my_hint.set(my_system.borrow().hint());
-        my_drawing_area.queue_draw();
+        my_game.show_hint();
+    });
+    let levels_button = Button::builder().label(tr("Levels")).build();
+    let my_game = Rc::clone(&game);
+    levels_button.connect_clicked(move |_| {
+        show_level_select(&my_game);
+    });
+    let solver_button = Button::builder().label(tr("Solver")).build();
+    let my_game = Rc::clone(&game);
+    solver_button.connect_clicked(move |button| {
+        if my_game.field_system.borrow().is_some() {
+            show_error(
+                button.root().and_downcast::<gtk4::Window>().as_ref(),
+                tr("The solver only works with real numbers."),
+            );
+            return;
+        }
+        show_watch_window(&my_game);
+    });
+    let matrices_button = ToggleButton::builder().label(tr("Matrices")).build();
+    let my_game = Rc::clone(&game);
+    matrices_button.connect_toggled(move |button| {
+        my_game.matrix_panel.set_visible(button.is_active());
+    });
+    let graph_button = ToggleButton::builder().label(tr("Graph")).build();
+    let my_game = Rc::clone(&game);
+    graph_button.connect_toggled(move |button| {
+        my_game.geometry_panel.set_visible(button.is_active());
+    });
+    let pivots_button = ToggleButton::builder().label(tr("Pivots")).build();
+    let my_game = Rc::clone(&game);
+    pivots_button.connect_toggled(move |button| {
+        my_game.show_structure.set(button.is_active());
+        my_game.drawing_area.queue_draw();
+    });
+    let settings_button = Button::builder().label(tr("Settings")).build();
+    let my_game = Rc::clone(&game);
+    settings_button.connect_clicked(move |_| {
+        show_settings_window(&my_game);
     });
-    let help_button = Button::builder().label("Help").build();
+    let export_button = Button::builder().label(tr("Export")).build();
+    let my_game = Rc::clone(&game);
+    export_button.connect_clicked(move |button| {
+        let parent = button.root().and_downcast::<gtk4::Window>();
+        //The history would describe the operations on the real stand-in for the system.
+        if my_game.field_system.borrow().is_some() {
+            show_error(
+                parent.as_ref(),
+                tr("Only systems of real numbers can be exported."),
+            );
+            return;
+        }
+        let system = my_game.system.borrow().clone();
+        let start_system = my_game.start_system.borrow().clone();
+        let operations = my_game.operations.borrow().clone();
+        let hint = my_game.hint.get();
+        let number_format = my_game.number_format.get();
+        let my_parent = parent.clone();
+        choose_save_path(
+            parent.as_ref(),
+            tr("Export"),
+            "solution.tex",
+            &[
+                ("hint", tr("Include hint in images"), &[]),
+                ("history", tr("Include history in images"), &[]),
+                (
+                    "scale",
+                    tr("Image scale"),
+                    &[("1", "1×"), ("2", "2×"), ("3", "3×"), ("4", "4×")],
+                ),
+            ],
+            move |path, dialog| {
+                let result: Result<(), Box<dyn std::error::Error>> =
+                    if path.extension().is_some_and(|extension| extension == "tex") {
+                        std::fs::write(&path, export_latex(&start_system, &operations))
+                            .map_err(Into::into)
+                    } else if let Some(format) = ImageFormat::from_path(&path) {
+                        let options = ExportOptions {
+                            scale: dialog
+                                .choice("scale")
+                                .and_then(|scale| scale.parse().ok())
+                                .unwrap_or(1.0),
+                            hint: if dialog.choice("hint").as_deref() == Some("true") {
+                                hint
+                            } else {
+                                None
+                            },
+                            history: if dialog.choice("history").as_deref() == Some("true") {
+                                Some((&start_system, &operations))
+                            } else {
+                                None
+                            },
+                            number_format,
+                        };
+                        export_image(&path, format, &system, &options)
+                    } else {
+                        Err(tr("the file name must end in .tex, .svg, .png, or .pdf").into())
+                    };
+                if let Err(error) = result {
+                    show_error(
+                        my_parent.as_ref(),
+                        &tr_format("Could not export {}: {}", &[&path.display(), &error]),
+                    );
+                }
+            },
+        );
+    });
+    let help_button = Button::builder().label(tr("Help")).build();
+    let my_game = Rc::clone(&game);
     help_button.connect_clicked(move |_| {
         let notebook = Notebook::new();
         let about = Label::builder()
             .use_markup(true)
             .label(
-                format!("<big>Gaussian Elimination Game</big>\n{}\nVersion {}\n\n<small>BSD 3-Clause \"New\" or \"Revised\" License\nCopyright 2025 UxuginPython\n{}</small>", env!("CARGO_PKG_DESCRIPTION"), env!("CARGO_PKG_VERSION"), env!("CARGO_PKG_REPOSITORY")),
+                format!("<big>{}</big>\n{}\n{}\n\n<small>BSD 3-Clause \"New\" or \"Revised\" License\nCopyright 2025 UxuginPython\n{}</small>", tr("Gaussian Elimination Game"), tr(env!("CARGO_PKG_DESCRIPTION")), tr_format("Version {}", &[&env!("CARGO_PKG_VERSION")]), env!("CARGO_PKG_REPOSITORY")),
             )
             .margin_top(20)
             .margin_bottom(20)
             .margin_start(20)
             .margin_end(20)
             .build();
-        let about_tab_label = Label::builder().label("About").build();
+        let about_tab_label = Label::builder().label(tr("About")).build();
         let about_gauss = Label::builder()
             .wrap(true)
-            .label("Gaussian elimination is a method of solving linear systems of equations named after mathematician Carl Friedrich Gauss. It arranges the coefficients and solutions of the equations into a matrix and then allows three operations: swapping two rows, scaling a row, and adding a multiple of a row to another. These operations are performed until the coefficients form the identity matrix (called reduced row echelon form) if a unique solution exists.")
+            .label(tr("Gaussian elimination is a method of solving linear systems of equations named after mathematician Carl Friedrich Gauss. It arranges the coefficients and solutions of the equations into a matrix and then allows three operations: swapping two rows, scaling a row, and adding a multiple of a row to another. These operations are performed until the coefficients form the identity matrix (called reduced row echelon form) if a unique solution exists."))
             .margin_top(10)
             .margin_bottom(10)
             .margin_start(10)
             .margin_end(10)
             .build();
-        let about_gauss_tab_label = Label::builder().label("About Gaussian Elimination").build();
-        let how_to_play = Label::builder()
+        let about_gauss_tab_label = Label::builder().label(tr("About Gaussian Elimination")).build();
+        let how_to_play_text = Label::builder()
             .wrap(true)
-            .label("To swap two rows, drag from the circle to the left of one to the circle of the other.\nTo scale a row to make a coefficient 1, click the coefficient.\nTo add a multiple of a row to another row to make a coefficient 0, drag from the row's circle to the coefficient.\nClick \"Hint\" for a suggestion for what to do.\nClick \"New\" to generate a new random system.")
+            .label(HOW_TO_PLAY.map(tr).join("\n"))
+            .build();
+        let tutorial_button = Button::builder()
+            .label(tr("Start Tutorial"))
+            .halign(gtk4::Align::Start)
+            .build();
+        let how_to_play = gtk4::Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(10)
             .margin_top(10)
             .margin_bottom(10)
             .margin_start(10)
             .margin_end(10)
             .build();
-        let how_to_play_tab_label = Label::builder().label("How to Play").build();
+        how_to_play.append(&tutorial_button);
+        how_to_play.append(&how_to_play_text);
+        let how_to_play_tab_label = Label::builder().label(tr("How to Play")).build();
         notebook.append_page(&about, Some(&about_tab_label));
         notebook.append_page(&about_gauss, Some(&about_gauss_tab_label));
+        let statistics = Label::builder()
+            .wrap(true)
+            .selectable(true)
+            .label(my_game.stats.borrow().describe())
+            .margin_top(10)
+            .margin_bottom(10)
+            .margin_start(10)
+            .margin_end(10)
+            .build();
+        let statistics_tab_label = Label::builder().label(tr("Statistics")).build();
         notebook.append_page(&how_to_play, Some(&how_to_play_tab_label));
+        let achievements = Label::builder()
+            .wrap(true)
+            .label(describe_achievements(&my_game.stats.borrow()))
+            .margin_top(10)
+            .margin_bottom(10)
+            .margin_start(10)
+            .margin_end(10)
+            .build();
+        let achievements_tab_label = Label::builder().label(tr("Achievements")).build();
+        notebook.append_page(&statistics, Some(&statistics_tab_label));
+        notebook.append_page(&achievements, Some(&achievements_tab_label));
         let help_window = gtk4::Window::builder()
-            .title("Help")
+            .title(tr("Help"))
             .child(&notebook)
             .build();
         help_window.set_default_width(200);
+        let my_my_game = Rc::clone(&my_game);
+        let my_help_window = help_window.clone();
+        tutorial_button.connect_clicked(move |_| {
+            my_my_game.start_tutorial();
+            my_help_window.close();
+        });
         help_window.present();
     });
     button_box.append(&new_button);
+    button_box.append(&mode_drop_down);
+    button_box.append(&levels_button);
     button_box.append(&hint_button);
+    button_box.append(&solver_button);
+    button_box.append(&matrices_button);
+    button_box.append(&graph_button);
+    button_box.append(&pivots_button);
+    button_box.append(&export_button);
+    button_box.append(&settings_button);
     button_box.append(&help_button);
     main_box.append(&button_box);
-    main_box.append(&drawing_area);
-    let my_system = Rc::clone(&system);
-    let my_hint = Rc::clone(&hint);
-    drawing_area.set_draw_func(move |_drawing_area, context, _width, _height| {
-        context.line_to(BOX_SIZE * 1.5, 0.0);
-        context.line_to(BOX_SIZE, 0.0);
-        context.line_to(BOX_SIZE, BOX_SIZE * SYSTEM_SIZE as f64);
-        context.line_to(BOX_SIZE * 1.5, BOX_SIZE * SYSTEM_SIZE as f64);
-        context.stroke().unwrap();
-        context.line_to((SYSTEM_SIZE + 2) as f64 * BOX_SIZE - 0.5 * BOX_SIZE, 0.0);
-        context.line_to((SYSTEM_SIZE + 2) as f64 * BOX_SIZE, 0.0);
-        context.line_to(
-            (SYSTEM_SIZE + 2) as f64 * BOX_SIZE,
-            BOX_SIZE * SYSTEM_SIZE as f64,
-        );
-        context.line_to(
-            (SYSTEM_SIZE + 2) as f64 * BOX_SIZE - 0.5 * BOX_SIZE,
-            BOX_SIZE * SYSTEM_SIZE as f64,
-        );
-        context.stroke().unwrap();
-        context.line_to((SYSTEM_SIZE + 1) as f64 * BOX_SIZE, 0.0);
-        context.line_to(
-            (SYSTEM_SIZE + 1) as f64 * BOX_SIZE,
-            BOX_SIZE * SYSTEM_SIZE as f64,
-        );
-        context.stroke().unwrap();
-        context.set_font_size(18.0);
-        for i in 0..SYSTEM_SIZE {
-            for j in 0..SYSTEM_SIZE {
-                let (x, y) = CanvasItem::Coefficient(i, j).get_center();
-                draw_text_centered(
-                    context,
-                    x,
-                    y,
-                    &format_float(my_system.borrow().equations[i].coefficients[j]),
-                );
-            }
+    main_box.append(&game.hud);
+    //The toast slides up over the bottom of the board.
+    let board_overlay = gtk4::Overlay::builder().child(&drawing_area).build();
+    board_overlay.add_overlay(&game.toast);
+    let board_box = gtk4::Box::builder()
+        .orientation(Orientation::Horizontal)
+        .build();
+    board_box.append(&board_overlay);
+    board_box.append(&game.matrix_panel);
+    board_box.append(&game.lu_panel);
+    board_box.append(&game.geometry_panel);
+    main_box.append(&board_box);
+    main_box.append(&game.status);
+    let my_game = Rc::clone(&game);
+    drawing_area.set_draw_func(move |drawing_area, context, width, height| {
+        let selected_row = my_game.selected_row.get();
+        let hint = my_game.hint.get();
+        let highlights = my_game.tutorial_highlights();
+        let mistakes = my_game.mistake_items.borrow();
+        let style = BoardStyle {
+            box_size: box_size(width as f64, height as f64, my_game.system.borrow().size()),
+            number_format: my_game.number_format.get(),
+            palette: Palette::for_widget(my_game.color_scheme.get(), drawing_area),
+            structure: my_game.show_structure.get(),
+        };
+        match my_game.field_system.borrow().as_ref() {
+            Some(FieldSystem::Complex(system)) => draw_board(
+                context,
+                system,
+                style,
+                selected_row,
+                hint,
+                &highlights,
+                &mistakes,
+            ),
+            Some(FieldSystem::Parameter(system)) => draw_board(
+                context,
+                &system.system,
+                style,
+                selected_row,
+                hint,
+                &highlights,
+                &mistakes,
+            ),
+            _ => draw_board(
+                context,
+                &my_game.system.borrow(),
+                style,
+                selected_row,
+                hint,
+                &highlights,
+                &mistakes,
+            ),
         }
-        for i in 0..SYSTEM_SIZE {
-            let (x, y) = CanvasItem::Solution(i).get_center();
-            draw_text_centered(
+    });
+    let my_game = Rc::clone(&game);
+    game.matrix_panel
+        .set_draw_func(move |drawing_area, context, width, height| {
+            if my_game.field_system.borrow().is_some() {
+                draw_real_only(context, width as f64, height as f64);
+                return;
+            }
+            let palette = Palette::for_widget(my_game.color_scheme.get(), drawing_area);
+            set_color(context, palette.foreground);
+            draw_elementary_matrices(
                 context,
-                x,
-                y,
-                &format_float(my_system.borrow().equations[i].solution),
+                &my_game.start_system.borrow(),
+                &my_game.operations.borrow(),
+                my_game.number_format.get(),
             );
-        }
-        match my_selected_row.get() {
-            Some(i) => {
-                context.set_source_rgb(0.0, 0.5, 1.0);
-                context.arc(
-                    0.5 * BOX_SIZE,
-                    i as f64 * BOX_SIZE + 0.5 * BOX_SIZE,
-                    0.25 * BOX_SIZE,
-                    0.0,
-                    std::f64::consts::PI * 2.0,
+        });
+    let my_game = Rc::clone(&game);
+    game.lu_panel
+        .set_draw_func(move |drawing_area, context, _width, _height| {
+            if let Some(lu) = my_game.lu.borrow().as_ref() {
+                let palette = Palette::for_widget(my_game.color_scheme.get(), drawing_area);
+                set_color(context, palette.foreground);
+                draw_lu(
+                    context,
+                    lu,
+                    &my_game.system.borrow(),
+                    my_game.number_format.get(),
                 );
-                context.fill().unwrap();
-                context.set_source_rgb(0.0, 0.0, 0.0);
             }
-            None => {}
-        }
-        context.move_to(0.75 * BOX_SIZE, 0.5 * BOX_SIZE);
-        for i in 0..SYSTEM_SIZE {
-            context.arc(
-                0.5 * BOX_SIZE,
-                i as f64 * BOX_SIZE + 0.5 * BOX_SIZE,
-                0.25 * BOX_SIZE,
-                0.0,
-                std::f64::consts::PI * 2.0,
+        });
+    let orbit = Rc::new(Cell::new(Orbit::default()));
+    let my_game = Rc::clone(&game);
+    let my_orbit = Rc::clone(&orbit);
+    game.geometry_panel
+        .set_draw_func(move |_drawing_area, context, width, height| {
+            if my_game.field_system.borrow().is_some() {
+                draw_real_only(context, width as f64, height as f64);
+                return;
+            }
+            draw_geometry(
+                context,
+                width as f64,
+                height as f64,
+                &my_game.system.borrow(),
+                my_orbit.get(),
             );
-            context.stroke().unwrap();
-        }
-        if let Some((equation, coefficient, with)) = my_hint.get() {
-            let (start_x, start_y) = CanvasItem::Circle(with).get_center();
-            let (end_x, end_y) = CanvasItem::Coefficient(equation, coefficient).get_center();
-            context.set_source_rgb(0.0, 0.0, 1.0);
-            context.line_to(start_x, start_y);
-            context.line_to(end_x, end_y);
-            context.stroke().unwrap();
-        }
+        });
+    let orbit_drag = GestureDrag::new();
+    let drag_start_orbit = Rc::new(Cell::new(Orbit::default()));
+    let my_orbit = Rc::clone(&orbit);
+    let my_drag_start_orbit = Rc::clone(&drag_start_orbit);
+    orbit_drag.connect_drag_begin(move |_, _, _| {
+        my_drag_start_orbit.set(my_orbit.get());
     });
+    let my_game = Rc::clone(&game);
+    orbit_drag.connect_drag_update(move |_, x, y| {
+        orbit.set(drag_start_orbit.get().turned(x, y));
+        my_game.geometry_panel.queue_draw();
+    });
+    game.geometry_panel.add_controller(orbit_drag);
     let left_click = GestureClick::new();
     left_click.set_button(1);
-    let my_drawing_area = drawing_area.clone();
-    let my_system = Rc::clone(&system);
+    let my_game = Rc::clone(&game);
     left_click.connect_pressed(move |_, _, x, y| {
-        let canvas_item = CanvasItem::from_coordinates(x, y);
-        if let CanvasItem::Coefficient(equation, coefficient) = canvas_item {
-            if my_system
-                .borrow()
-                .can_make_coefficient_1(equation, coefficient)
-            {
-                my_system
-                    .borrow_mut()
-                    .make_coefficient_1(equation, coefficient);
+        let canvas_item = my_game.item_at(x, y);
+        if let Some(CanvasItem::Coefficient(equation, coefficient)) = canvas_item {
+            let operation = RowOperation::MakeCoefficient1(equation, coefficient);
+            if my_game.should_apply(operation) {
+                my_game.player_operation(operation);
             }
         }
-        my_drawing_area.queue_draw();
+        my_game.drawing_area.queue_draw();
     });
     drawing_area.add_controller(left_click);
     let drag = GestureDrag::new();
     let start_coords = Rc::new(Cell::new((0.0, 0.0)));
     let my_start_coords = Rc::clone(&start_coords);
-    let my_drawing_area = drawing_area.clone();
-    let my_selected_row = Rc::clone(&selected_row);
+    let my_game = Rc::clone(&game);
     drag.connect_drag_begin(move |_, x, y| {
         my_start_coords.set((x, y));
-        if let CanvasItem::Circle(i) = CanvasItem::from_coordinates(x, y) {
-            my_selected_row.set(Some(i));
+        if let Some(CanvasItem::Circle(i)) = my_game.item_at(x, y) {
+            my_game.selected_row.set(Some(i));
         }
     });
     let my_start_coords = Rc::clone(&start_coords);
-    let my_selected_row = Rc::clone(&selected_row);
-    let my_hint = Rc::clone(&hint);
+    let my_game = Rc::clone(&game);
     drag.connect_drag_end(move |_, relative_x, relative_y| {
-        my_selected_row.set(None);
+        my_game.selected_row.set(None);
         let (start_x, start_y) = my_start_coords.get();
         let end_x = start_x + relative_x;
         let end_y = start_y + relative_y;
-        let start_item = CanvasItem::from_coordinates(start_x, start_y);
-        let end_item = CanvasItem::from_coordinates(end_x, end_y);
-        if let CanvasItem::Circle(start_equation) = start_item {
-            if let CanvasItem::Circle(end_equation) = end_item {
-                system
-                    .borrow_mut()
-                    .switch_rows(start_equation, end_equation);
-                my_hint.set(None);
-            } else if let CanvasItem::Coefficient(end_equation, end_coefficient) = end_item {
-                if start_equation != end_equation
-                    && system.borrow().can_make_coefficient_0_with_row(
-                        end_equation,
-                        end_coefficient,
-                        start_equation,
-                    )
-                {
-                    system.borrow_mut().make_coefficient_0_with_row(
-                        end_equation,
-                        end_coefficient,
-                        start_equation,
-                    );
-                    my_hint.set(None);
+        let start_item = my_game.item_at(start_x, start_y);
+        let end_item = my_game.item_at(end_x, end_y);
+        if let Some(CanvasItem::Circle(start_equation)) = start_item {
+            if let Some(CanvasItem::Circle(end_equation)) = end_item {
+                if start_equation != end_equation {
+                    my_game
+                        .player_operation(RowOperation::SwitchRows(start_equation, end_equation));
+                }
+                my_game.clear_hint();
+            } else if let Some(CanvasItem::Coefficient(end_equation, end_coefficient)) = end_item {
+                let operation = RowOperation::MakeCoefficient0WithRow(
+                    end_equation,
+                    end_coefficient,
+                    start_equation,
+                );
+                if my_game.should_apply(operation) {
+                    my_game.player_operation(operation);
                 }
             }
         }
-        my_drawing_area.queue_draw();
+        my_game.drawing_area.queue_draw();
     });
     drawing_area.add_controller(drag);
     let window = ApplicationWindow::builder()
         .application(app)
-        .title("Gaussian Elimination Game")
+        .title(tr("Gaussian Elimination Game"))
         .child(&main_box)
         .build();
     window.present();
 }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn system() -> System {
+        System::new(vec![
+            Equation::new(vec![0.0, 2.0, 1.0], 1.0),
+            Equation::new(vec![2.0, 1.0, 1.0], 2.0),
+            Equation::new(vec![3.0, 1.0, 2.0], 3.0),
+        ])
+    }
+    #[test]
+    fn explains_hints() {
+        let mut system = system();
+        let explain = |system: &System| explain_hint(system, NumberFormat::Fraction).unwrap();
+        assert_eq!(
+            explain(&system),
+            "Column 1 needs a pivot in row 1, but that coefficient is 0. Row 2 has a coefficient that isn't 0 there, so switch rows 1 and 2."
+        );
+        system.apply(RowOperation::SwitchRows(0, 1));
+        assert_eq!(
+            explain(&system),
+            "The pivot in column 1 must be 1, so divide row 1 by 2."
+        );
+        system.apply(RowOperation::MakeCoefficient1(0, 0));
+        assert_eq!(
+            explain(&system),
+            "Column 1 below the pivot must be 0, so subtract 3 times row 1 from row 3."
+        );
+        system.equations[2].coefficients[0] = -1.0;
+        assert_eq!(
+            explain(&system),
+            "Column 1 below the pivot must be 0, so add row 1 to row 3."
+        );
+        let identity = System::new(vec![
+            Equation::new(vec![1.0, 0.0], 1.0),
+            Equation::new(vec![0.0, 1.0], 2.0),
+        ]);
+        assert_eq!(explain_hint(&identity, NumberFormat::Fraction), None);
+    }
+    #[test]
+    fn explains_hints_in_other_fields() {
+        let m = |value| Modular::new(value, 7);
+        let modular = System::new(vec![
+            Equation::new(vec![m(3), m(1)], m(2)),
+            Equation::new(vec![m(1), m(1)], m(1)),
+        ]);
+        assert_eq!(
+            modular.explain_modular_hint().unwrap(),
+            "The pivot in column 1 must be 1, so divide row 1 by 3. That's multiplying by 5, since 3 × 5 = 1 mod 7."
+        );
+        let complex = System::new(vec![
+            Equation::new(
+                vec![Complex::new(1.0, 0.0), Complex::new(0.0, 0.0)],
+                Complex::new(1.0, 0.0),
+            ),
+            Equation::new(
+                vec![Complex::new(1.0, 1.0), Complex::new(1.0, 0.0)],
+                Complex::new(0.0, 0.0),
+            ),
+        ]);
+        assert_eq!(
+            explain_hint(&complex, NumberFormat::Fraction).unwrap(),
+            "Column 1 below the pivot must be 0, so subtract (1+i) times row 1 from row 2."
+        );
+        let rational = System::new(vec![
+            Equation::new(
+                vec![Rational::integer(1), Rational::integer(2)],
+                Rational::integer(1),
+            ),
+            Equation::new(
+                vec![Rational::new(-3, 2), Rational::integer(1)],
+                Rational::integer(0),
+            ),
+        ]);
+        assert_eq!(
+            explain_hint(&rational, NumberFormat::Fraction).unwrap(),
+            "Column 1 below the pivot must be 0, so add 3/2 times row 1 to row 2."
+        );
+    }
+    #[test]
+    fn parenthesizes_sums() {
+        assert_eq!(parenthesize_sum("-3/2".to_string()), "-3/2");
+        assert_eq!(parenthesize_sum("1.5e-4".to_string()), "1.5e-4");
+        assert_eq!(parenthesize_sum("1-2i".to_string()), "(1-2i)");
+        assert_eq!(parenthesize_sum("k+1".to_string()), "(k+1)");
+        assert_eq!(parenthesize_sum("(k+1)/k".to_string()), "(k+1)/k");
+    }
+    #[test]
+    fn describes_operations() {
+        let system = system();
+        let describe = |operation| describe_operation(&system, operation, NumberFormat::Fraction);
+        assert_eq!(describe(RowOperation::SwitchRows(0, 1)), "R1 ↔ R2");
+        assert_eq!(describe(RowOperation::MakeCoefficient1(1, 0)), "1/2R2 → R2");
+        assert_eq!(
+            describe(RowOperation::MakeCoefficient0WithRow(2, 0, 1)),
+            "R3 - 3/2R2 → R3"
+        );
+        assert_eq!(
+            describe(RowOperation::MakeCoefficient0WithRow(0, 2, 1)),
+            "R1 - R2 → R1"
+        );
+    }
+    #[test]
+    fn describes_operations_for_other_programs() {
+        let system = system();
+        assert_eq!(
+            describe_operation_with_point(
+                &system,
+                RowOperation::MakeCoefficient0WithRow(2, 0, 1),
+                NumberFormat::Decimal
+            ),
+            "R3 - 1.5R2 → R3"
+        );
+        assert_eq!(
+            format_number_with_point(-0.25, NumberFormat::Decimal),
+            "-0.25"
+        );
+        assert_eq!(
+            format_number_with_point(-0.25, NumberFormat::Fraction),
+            "-1/4"
+        );
+    }
+}
@@ -0,0 +1,277 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+use super::*;
+///An operation that doesn't get the system any closer to being solved
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mistake {
+    ///Made a coefficient in this column nonzero again after everything but its pivot was 0
+    RefilledColumn(usize),
+    ///Switched back the two rows that were just switched
+    UndidSwap,
+    ///Scaled a row right back to what it was before it was last scaled
+    UndidScaling,
+}
+impl Mistake {
+    ///What the operation that was this mistake changed, to point out on the board
+    pub fn items(self, operation: RowOperation) -> Vec<CanvasItem> {
+        match (self, operation) {
+            (_, RowOperation::SwitchRows(a, b)) => {
+                vec![CanvasItem::Circle(a), CanvasItem::Circle(b)]
+            }
+            (
+                Self::RefilledColumn(column),
+                RowOperation::MakeCoefficient0WithRow(equation, _, _),
+            ) => {
+                vec![CanvasItem::Coefficient(equation, column)]
+            }
+            (_, RowOperation::MakeCoefficient1(equation, coefficient))
+            | (_, RowOperation::MakeCoefficient0WithRow(equation, coefficient, _)) => {
+                vec![CanvasItem::Coefficient(equation, coefficient)]
+            }
+        }
+    }
+    pub fn feedback(self) -> String {
+        match self {
+            Self::RefilledColumn(column) => tr_format(
+                "Column {} was already cleared, and that put a number other than 0 back in it. Try using a row with a 0 in that column instead.",
+                &[&(column + 1)],
+            ),
+            Self::UndidSwap => {
+                tr("That switched the same two rows back. Swapping twice doesn't change anything.")
+                    .to_string()
+            }
+            Self::UndidScaling => tr(
+                "That scaled the row right back to what it was before, which undid the last move.",
+            )
+            .to_string(),
+        }
+    }
+}
+///Checks if two equations are the same apart from rounding error.
+fn nearly_equal(a: &Equation, b: &Equation) -> bool {
+    let close = |x: f64, y: f64| (x - y).abs() <= 1e-9 * x.abs().max(y.abs()).max(1.0);
+    a.coefficients
+        .iter()
+        .zip(b.coefficients.iter())
+        .all(|(x, y)| close(*x, *y))
+        && close(a.solution, b.solution)
+}
+impl System {
+    ///Counts the coefficients in a column that aren't 0.
+    fn nonzero_in_column(&self, column: usize) -> usize {
+        self.equations
+            .iter()
+            .filter(|equation| equation.coefficients[column] != 0.0)
+            .count()
+    }
+    ///Checks if the column has been cleared around a pivot: its only coefficient that isn't 0 is
+    ///a 1 at the start of its row.
+    fn is_cleared_column(&self, column: usize) -> bool {
+        self.nonzero_in_column(column) == 1
+            && self.equations.iter().any(|equation| {
+                equation.coefficients[column] == 1.0
+                    && equation.coefficients[..column]
+                        .iter()
+                        .all(|coefficient| *coefficient == 0.0)
+            })
+    }
+    ///Checks if doing `operation` to the system would be a mistake. `previous` is the operation
+    ///done just before this one along with the system it was done to, and `cleared_columns` says
+    ///which columns the player has cleared so far.
+    pub fn find_mistake(
+        &self,
+        operation: RowOperation,
+        previous: Option<(RowOperation, &System)>,
+        cleared_columns: &[bool],
+    ) -> Option<Mistake> {
+        let mut after = self.clone();
+        after.apply(operation);
+        match (operation, previous) {
+            (RowOperation::SwitchRows(a, b), Some((RowOperation::SwitchRows(c, d), _)))
+                if (a, b) == (c, d) || (a, b) == (d, c) =>
+            {
+                Some(Mistake::UndidSwap)
+            }
+            (
+                RowOperation::MakeCoefficient1(equation, _),
+                Some((RowOperation::MakeCoefficient1(previous_equation, _), before_previous)),
+            ) if equation == previous_equation
+                && nearly_equal(
+                    &after.equations[equation],
+                    &before_previous.equations[equation],
+                ) =>
+            {
+                Some(Mistake::UndidScaling)
+            }
+            (RowOperation::MakeCoefficient0WithRow(..), _) => (0..self.size())
+                .find(|&column| {
+                    cleared_columns[column]
+                        && self.nonzero_in_column(column) == 1
+                        && after.nonzero_in_column(column) > 1
+                })
+                .map(Mistake::RefilledColumn),
+            _ => None,
+        }
+    }
+}
+///What `find_mistake` needs to know about the operations done so far: which columns they cleared
+///at some point, and the last one along with the system before it. Columns that started out
+///cleared don't count, since the player didn't do anything to clear them.
+fn replay(
+    start: &System,
+    operations: &[RowOperation],
+) -> (Vec<bool>, Option<(RowOperation, System)>) {
+    let mut system = start.clone();
+    let mut cleared_columns = vec![false; system.size()];
+    let mut previous = None;
+    for operation in operations {
+        let before = system.clone();
+        system.apply(*operation);
+        previous = Some((*operation, before));
+        for (column, cleared) in cleared_columns.iter_mut().enumerate() {
+            *cleared |= system.is_cleared_column(column);
+        }
+    }
+    (cleared_columns, previous)
+}
+impl Game {
+    ///Checks if the player is about to make a mistake so that they can be told about it after the
+    ///operation. Only real systems are checked since this replays the operations with real
+    ///numbers.
+    pub(crate) fn find_mistake(&self, operation: RowOperation) -> Option<Mistake> {
+        if self.field_system.borrow().is_some() {
+            return None;
+        }
+        let (cleared_columns, previous) =
+            replay(&self.start_system.borrow(), &self.operations.borrow());
+        self.system.borrow().find_mistake(
+            operation,
+            previous
+                .as_ref()
+                .map(|(last, before_previous)| (*last, before_previous)),
+            &cleared_columns,
+        )
+    }
+    ///Tells the player about a mistake they made and counts it.
+    pub(crate) fn report_mistake(&self, mistake: Mistake) {
+        self.show_toast(&mistake.feedback());
+        let mut stats = self.stats.borrow_mut();
+        stats.record_mistake(mistake);
+        if let Err(error) = stats.save() {
+            eprintln!("Could not save statistics: {}", error);
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    ///Finds a mistake in doing `operation` after `operations`.
+    fn mistake_after(
+        start: &System,
+        operations: &[RowOperation],
+        operation: RowOperation,
+    ) -> Option<Mistake> {
+        let mut system = start.clone();
+        for operation in operations {
+            system.apply(*operation);
+        }
+        let (cleared_columns, previous) = replay(start, operations);
+        system.find_mistake(
+            operation,
+            previous
+                .as_ref()
+                .map(|(last, before_previous)| (*last, before_previous)),
+            &cleared_columns,
+        )
+    }
+    #[test]
+    fn finds_refilled_columns() {
+        let start = System::new(vec![
+            Equation::new(vec![2.0, 1.0], 3.0),
+            Equation::new(vec![4.0, 3.0], 7.0),
+        ]);
+        let operations = [
+            RowOperation::MakeCoefficient0WithRow(1, 0, 0),
+            RowOperation::MakeCoefficient1(0, 0),
+        ];
+        //Clearing column 2 with the first row puts a number back in column 1.
+        assert_eq!(
+            mistake_after(
+                &start,
+                &operations,
+                RowOperation::MakeCoefficient0WithRow(1, 1, 0)
+            ),
+            Some(Mistake::RefilledColumn(0))
+        );
+        assert_eq!(
+            mistake_after(
+                &start,
+                &operations,
+                RowOperation::MakeCoefficient0WithRow(0, 1, 1)
+            ),
+            None
+        );
+    }
+    #[test]
+    fn following_hints_is_never_a_mistake() {
+        //Column 3 starts with only one number that isn't 0, but it isn't a pivot the player made,
+        //so filling it in isn't undoing anything.
+        let start = System::new(vec![
+            Equation::new(vec![1.0, 0.0, 5.0], 1.0),
+            Equation::new(vec![2.0, 1.0, 0.0], 2.0),
+            Equation::new(vec![3.0, 1.0, 0.0], 3.0),
+        ]);
+        let mut operations = Vec::new();
+        let mut system = start.clone();
+        while let Some(hint) = system.hint() {
+            assert_eq!(mistake_after(&start, &operations, hint), None, "{:?}", hint);
+            system.apply(hint);
+            operations.push(hint);
+        }
+        assert!(system.is_reduced_row_echelon_form());
+    }
+    #[test]
+    fn finds_undone_moves() {
+        let start = System::new(vec![
+            Equation::new(vec![0.0, 2.0], 4.0),
+            Equation::new(vec![3.0, 1.0], 5.0),
+        ]);
+        let swap = RowOperation::SwitchRows(0, 1);
+        assert_eq!(
+            mistake_after(&start, &[swap], RowOperation::SwitchRows(1, 0)),
+            Some(Mistake::UndidSwap)
+        );
+        assert_eq!(mistake_after(&start, &[], swap), None);
+        let scale_first = RowOperation::MakeCoefficient1(0, 1);
+        //Scaling by the other coefficient's inverse gets the row back to what it was.
+        let mut scaled = start.clone();
+        scaled.equations[0] = Equation::new(vec![1.0, 2.0], 4.0);
+        assert_eq!(
+            mistake_after(
+                &scaled,
+                &[RowOperation::MakeCoefficient1(0, 1)],
+                RowOperation::MakeCoefficient1(0, 0)
+            ),
+            Some(Mistake::UndidScaling)
+        );
+        assert_eq!(
+            mistake_after(&start, &[scale_first], RowOperation::MakeCoefficient1(1, 0)),
+            None
+        );
+    }
+    #[test]
+    fn outlines_what_changed() {
+        assert_eq!(
+            Mistake::UndidSwap.items(RowOperation::SwitchRows(0, 2)),
+            [CanvasItem::Circle(0), CanvasItem::Circle(2)]
+        );
+        assert_eq!(
+            Mistake::RefilledColumn(0).items(RowOperation::MakeCoefficient0WithRow(1, 1, 0)),
+            [CanvasItem::Coefficient(1, 0)]
+        );
+        assert_eq!(
+            Mistake::UndidScaling.items(RowOperation::MakeCoefficient1(1, 2)),
+            [CanvasItem::Coefficient(1, 2)]
+        );
+    }
+}
@@ -0,0 +1,322 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+use super::*;
+use std::time::{Duration, Instant};
+const TIME_ATTACK_LENGTH: Duration = Duration::from_secs(3 * 60);
+const SPRINT_PUZZLES: usize = 5;
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GameMode {
+    ///No goal, just a new system whenever "New" is clicked
+    #[default]
+    FreePlay,
+    ///Solve as many systems as possible before time runs out.
+    TimeAttack,
+    ///Solve a set number of systems as fast as possible.
+    Sprint,
+    ///Keep solving systems without using more operations than the solver would.
+    Survival,
+    ///The same system all day, made from the date
+    Daily,
+    ///Factor the coefficients into L and U instead of solving the system.
+    LuDecomposition,
+    ///Compare solving the system with solving it by Cramer's rule.
+    Cramer,
+    ///Every number is an integer mod this prime.
+    Modular(u32),
+    ///Every number is a complex number.
+    Complex,
+    ///The coefficients have a parameter k in them.
+    Parameter,
+}
+impl GameMode {
+    pub const ALL: [Self; 12] = [
+        Self::FreePlay,
+        Self::TimeAttack,
+        Self::Sprint,
+        Self::Survival,
+        Self::Daily,
+        Self::LuDecomposition,
+        Self::Cramer,
+        Self::Modular(5),
+        Self::Modular(7),
+        Self::Modular(11),
+        Self::Complex,
+        Self::Parameter,
+    ];
+    ///The name in the settings file
+    pub const fn id(self) -> &'static str {
+        match self {
+            Self::FreePlay => "free-play",
+            Self::TimeAttack => "time-attack",
+            Self::Sprint => "sprint",
+            Self::Survival => "survival",
+            Self::Daily => "daily",
+            Self::LuDecomposition => "lu-decomposition",
+            Self::Cramer => "cramer",
+            Self::Modular(5) => "mod-5",
+            Self::Modular(7) => "mod-7",
+            Self::Modular(11) => "mod-11",
+            Self::Modular(_) => "modular",
+            Self::Complex => "complex",
+            Self::Parameter => "parameter",
+        }
+    }
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.id() == id)
+    }
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::FreePlay => "Free Play",
+            Self::TimeAttack => "Time Attack",
+            Self::Sprint => "Sprint",
+            Self::Survival => "Survival",
+            Self::Daily => "Daily Puzzle",
+            Self::LuDecomposition => "LU Decomposition",
+            Self::Cramer => "Cramer's Rule",
+            Self::Modular(5) => "Mod 5",
+            Self::Modular(7) => "Mod 7",
+            Self::Modular(11) => "Mod 11",
+            Self::Modular(_) => "Modular Arithmetic",
+            Self::Complex => "Complex Numbers",
+            Self::Parameter => "Parameter k",
+        }
+    }
+}
+///Progress through one game of a mode other than free play
+#[derive(Clone, Debug)]
+pub struct Run {
+    pub mode: GameMode,
+    pub started: Instant,
+    pub solved: usize,
+    ///Operations over the whole run
+    pub moves: usize,
+    ///Operations on the current system
+    pub puzzle_moves: usize,
+    ///How many operations the solver needs for the current system
+    pub par: usize,
+}
+impl Run {
+    fn new(mode: GameMode) -> Self {
+        Self {
+            mode,
+            started: Instant::now(),
+            solved: 0,
+            moves: 0,
+            puzzle_moves: 0,
+            par: 0,
+        }
+    }
+    fn time_left(&self) -> Duration {
+        TIME_ATTACK_LENGTH.saturating_sub(self.started.elapsed())
+    }
+    fn hud_text(&self) -> String {
+        match self.mode {
+            GameMode::FreePlay
+            | GameMode::Daily
+            | GameMode::LuDecomposition
+            | GameMode::Cramer
+            | GameMode::Modular(_)
+            | GameMode::Complex
+            | GameMode::Parameter => String::new(),
+            GameMode::TimeAttack => tr_format(
+                "Time left {}   Solved {}",
+                &[&format_duration(self.time_left()), &self.solved],
+            ),
+            GameMode::Sprint => tr_format(
+                "Solved {} of {}   Time {}",
+                &[
+                    &self.solved,
+                    &SPRINT_PUZZLES,
+                    &format_duration(self.started.elapsed()),
+                ],
+            ),
+            GameMode::Survival => tr_format(
+                "Puzzle {}   Moves {} of {}",
+                &[&(self.solved + 1), &self.puzzle_moves, &self.par],
+            ),
+        }
+    }
+    fn summary(&self, heading: &str) -> String {
+        let time = self.started.elapsed();
+        let mut summary = tr_format(
+            "{}\n\nSolved: {}\nMoves: {}\nTime: {}",
+            &[
+                &tr(heading),
+                &self.solved,
+                &self.moves,
+                &format_duration(time),
+            ],
+        );
+        if self.solved > 0 {
+            summary.push_str(&tr_format(
+                "\nAverage time per system: {}",
+                &[&format_duration(time / self.solved as u32)],
+            ));
+        }
+        summary
+    }
+}
+///Formats as minutes and seconds, like 2:05.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+impl Game {
+    ///Starts a new game of `mode`, ending the current one without a summary. Free play just gets
+    ///a new system.
+    pub fn start_run(self: &Rc<Self>, mode: GameMode) {
+        self.stop_run_timer();
+        self.mode.set(mode);
+        match mode {
+            GameMode::FreePlay => {
+                *self.run.borrow_mut() = None;
+                self.hud.set_visible(false);
+                self.new_random_system();
+                return;
+            }
+            GameMode::Daily => {
+                *self.run.borrow_mut() = None;
+                self.start_daily();
+                return;
+            }
+            GameMode::LuDecomposition => {
+                *self.run.borrow_mut() = None;
+                self.start_lu();
+                return;
+            }
+            GameMode::Cramer => {
+                *self.run.borrow_mut() = None;
+                self.start_cramer();
+                return;
+            }
+            GameMode::Modular(modulus) => {
+                *self.run.borrow_mut() = None;
+                self.start_modular(modulus);
+                return;
+            }
+            GameMode::Complex => {
+                *self.run.borrow_mut() = None;
+                self.start_complex();
+                return;
+            }
+            GameMode::Parameter => {
+                *self.run.borrow_mut() = None;
+                self.start_parameter();
+                return;
+            }
+            _ => {}
+        }
+        *self.run.borrow_mut() = Some(Run::new(mode));
+        self.next_puzzle();
+        self.hud.set_visible(true);
+        if matches!(mode, GameMode::TimeAttack | GameMode::Sprint) {
+            let my_self = Rc::clone(self);
+            let timer = glib::timeout_add_seconds_local(1, move || {
+                let time_up = my_self.run.borrow().as_ref().is_some_and(|run| {
+                    run.mode == GameMode::TimeAttack && run.time_left().is_zero()
+                });
+                if time_up {
+                    //Returning Break removes the timer, so stop_run_timer mustn't remove it again.
+                    my_self.run_timer.borrow_mut().take();
+                    my_self.finish_run("Time's up!");
+                    return glib::ControlFlow::Break;
+                }
+                my_self.update_hud();
+                glib::ControlFlow::Continue
+            });
+            *self.run_timer.borrow_mut() = Some(timer);
+        }
+    }
+    ///Ends the current run without a summary.
+    pub(crate) fn stop_run(&self) {
+        self.stop_run_timer();
+        *self.run.borrow_mut() = None;
+        self.hud.set_visible(false);
+    }
+    fn stop_run_timer(&self) {
+        if let Some(timer) = self.run_timer.borrow_mut().take() {
+            timer.remove();
+        }
+    }
+    fn update_hud(&self) {
+        if let Some(run) = self.run.borrow().as_ref() {
+            self.hud.set_label(&run.hud_text());
+        }
+    }
+    ///Puts a new system with a unique solution on the board for the current run.
+    fn next_puzzle(&self) {
+        let system = loop {
+            let system = System::random(
+                self.size.get(),
+                self.difficulty.get(),
+                &mut *self.rng.borrow_mut(),
+            );
+            if system.has_unique_solution() {
+                break system;
+            }
+        };
+        if let Some(run) = self.run.borrow_mut().as_mut() {
+            run.par = system.solve().len();
+            run.puzzle_moves = 0;
+        }
+        self.set_system(system, None);
+        self.update_hud();
+    }
+    ///Ends the current run without a summary because the computer made moves or changed the
+    ///system, which wouldn't be fair to count.
+    pub(crate) fn end_run_for_computer(&self) {
+        if self.run.borrow().is_none() {
+            return;
+        }
+        self.stop_run();
+        self.show_toast(tr(
+            "The run ended because the computer took over. Click \"New\" to start again.",
+        ));
+    }
+    ///Counts a player's operation toward the current run and moves on to the next system or ends the run
+    ///if it's time to.
+    pub(crate) fn record_move(&self) {
+        let solved = self.is_solved();
+        let (mode, run_solved, out_of_moves) = {
+            let mut run = self.run.borrow_mut();
+            let Some(run) = run.as_mut() else {
+                return;
+            };
+            run.moves += 1;
+            run.puzzle_moves += 1;
+            if solved {
+                run.solved += 1;
+            }
+            (run.mode, run.solved, !solved && run.puzzle_moves >= run.par)
+        };
+        if solved {
+            if mode == GameMode::Sprint && run_solved == SPRINT_PUZZLES {
+                self.finish_run("Sprint finished!");
+            } else {
+                self.next_puzzle();
+            }
+        } else if mode == GameMode::Survival && out_of_moves {
+            self.finish_run("Out of moves!");
+        } else {
+            self.update_hud();
+        }
+    }
+    ///Ends the current run and shows how it went. "New" starts another run of the same mode.
+    fn finish_run(&self, heading: &str) {
+        self.stop_run_timer();
+        let Some(run) = self.run.borrow_mut().take() else {
+            return;
+        };
+        self.hud.set_visible(false);
+        let dialog = MessageDialog::builder()
+            .message_type(MessageType::Info)
+            .buttons(ButtonsType::Ok)
+            .text(tr(run.mode.name()))
+            .secondary_text(run.summary(heading))
+            .modal(true)
+            .build();
+        dialog.set_transient_for(self.drawing_area.root().and_downcast_ref::<gtk4::Window>());
+        dialog.connect_response(|dialog, _| dialog.destroy());
+        dialog.present();
+    }
+}
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!Modular arithmetic mode. Every number is an integer mod a prime, so there are no fractions, and
+//!scaling a row to make a coefficient 1 multiplies it by the coefficient's inverse. The board
+//!shows each number as its remainder, from 0 up to one less than the prime.
+use super::*;
+use rand::Rng;
+impl System<Modular> {
+    pub fn random_modular(size: usize, modulus: u32, rng: &mut impl Rng) -> Self {
+        let mut random = || Modular::new(rng.random_range(0..modulus as i64), modulus);
+        Self::new(
+            (0..size)
+                .map(|_| Equation::new((0..size).map(|_| random()).collect(), random()))
+                .collect(),
+        )
+    }
+    ///The remainders as real numbers for drawing the board
+    pub fn to_real(&self) -> System {
+        System::new(
+            self.equations
+                .iter()
+                .map(|equation| {
+                    Equation::new(
+                        equation
+                            .coefficients
+                            .iter()
+                            .map(|coefficient| coefficient.value() as f64)
+                            .collect(),
+                        equation.solution.value() as f64,
+                    )
+                })
+                .collect(),
+        )
+    }
+    ///Explains the hint like `explain_hint`, along with the inverse that dividing multiplies by.
+    pub fn explain_modular_hint(&self) -> Option<String> {
+        let mut explanation = explain_hint(self, NumberFormat::default())?;
+        if let Some(RowOperation::MakeCoefficient1(row, column)) = self.hint() {
+            let pivot = self.equations[row].coefficients[column];
+            let inverse = pivot.inverse()?;
+            explanation.push_str(&tr_format(
+                " That's multiplying by {}, since {} × {} = 1 mod {}.",
+                &[&inverse, &pivot, &inverse, &pivot.modulus()],
+            ));
+        }
+        Some(explanation)
+    }
+}
+impl Game {
+    ///Puts a system mod `modulus` with a unique solution on the board.
+    pub fn start_modular(&self, modulus: u32) {
+        let system = loop {
+            let system =
+                System::random_modular(self.size.get(), modulus, &mut *self.rng.borrow_mut());
+            if system.has_unique_solution() {
+                break system;
+            }
+        };
+        self.set_field_system(FieldSystem::Modular(system));
+        self.hud.set_label(&tr_format(
+            "Every number is mod {}. Clicking a coefficient multiplies its row by the coefficient's inverse mod {}.",
+            &[&modulus, &modulus],
+        ));
+        self.hud.set_visible(true);
+    }
+}
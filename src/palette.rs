@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!Colors for drawing the board. They follow the text color of the GTK theme so that everything can
+//!be seen on both light and dark themes, and there are high contrast and colorblind safe versions.
+use super::*;
+pub type Color = (f64, f64, f64);
+pub fn set_color(context: &gtk4::cairo::Context, color: Color) {
+    context.set_source_rgb(color.0, color.1, color.2);
+}
+///Which set of colors to use
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ColorScheme {
+    #[default]
+    Standard,
+    ///Pure black or white with darker or lighter colors and thicker lines
+    HighContrast,
+    ///Colors from the Okabe-Ito palette, which can be told apart with any kind of color blindness
+    Colorblind,
+}
+impl ColorScheme {
+    pub const ALL: [Self; 3] = [Self::Standard, Self::HighContrast, Self::Colorblind];
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Standard => "Standard",
+            Self::HighContrast => "High Contrast",
+            Self::Colorblind => "Colorblind Safe",
+        }
+    }
+    ///The name used in the settings file
+    pub const fn id(self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::HighContrast => "high-contrast",
+            Self::Colorblind => "colorblind",
+        }
+    }
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|scheme| scheme.id() == id)
+    }
+    ///The colors to draw with on a light or dark background. `foreground` is the theme's text
+    ///color, which is used as is except in high contrast.
+    pub fn palette(self, foreground: Color, dark: bool) -> Palette {
+        match (self, dark) {
+            (Self::Standard, false) => Palette {
+                foreground,
+                highlight: (1.0, 0.9, 0.4),
+                selection: (0.0, 0.5, 1.0),
+                hint: (0.0, 0.0, 1.0),
+                pivot: (0.1, 0.6, 0.2),
+                remaining: (1.0, 0.55, 0.2),
+                error: (0.85, 0.1, 0.1),
+                line_width: 1.0,
+            },
+            (Self::Standard, true) => Palette {
+                foreground,
+                highlight: (0.45, 0.38, 0.05),
+                selection: (0.3, 0.65, 1.0),
+                hint: (0.45, 0.65, 1.0),
+                pivot: (0.4, 0.8, 0.45),
+                remaining: (0.9, 0.5, 0.15),
+                error: (1.0, 0.4, 0.4),
+                line_width: 1.0,
+            },
+            (Self::HighContrast, false) => Palette {
+                foreground: (0.0, 0.0, 0.0),
+                highlight: (1.0, 0.85, 0.0),
+                selection: (0.0, 0.0, 0.6),
+                hint: (0.55, 0.0, 0.55),
+                pivot: (0.0, 0.4, 0.0),
+                remaining: (0.9, 0.4, 0.0),
+                error: (0.75, 0.0, 0.0),
+                line_width: 2.0,
+            },
+            (Self::HighContrast, true) => Palette {
+                foreground: (1.0, 1.0, 1.0),
+                highlight: (0.35, 0.3, 0.0),
+                selection: (0.4, 0.8, 1.0),
+                hint: (1.0, 0.6, 1.0),
+                pivot: (0.5, 1.0, 0.5),
+                remaining: (1.0, 0.6, 0.2),
+                error: (1.0, 0.45, 0.45),
+                line_width: 2.0,
+            },
+            (Self::Colorblind, false) => Palette {
+                foreground,
+                highlight: (0.94, 0.89, 0.26),
+                selection: (0.0, 0.45, 0.7),
+                hint: (0.8, 0.47, 0.65),
+                pivot: (0.0, 0.62, 0.45),
+                remaining: (0.9, 0.62, 0.0),
+                error: (0.84, 0.37, 0.0),
+                line_width: 1.0,
+            },
+            (Self::Colorblind, true) => Palette {
+                foreground,
+                highlight: (0.45, 0.42, 0.1),
+                selection: (0.34, 0.71, 0.91),
+                hint: (0.9, 0.62, 0.0),
+                pivot: (0.2, 0.75, 0.6),
+                remaining: (0.8, 0.47, 0.65),
+                error: (0.95, 0.45, 0.2),
+                line_width: 1.0,
+            },
+        }
+    }
+}
+///The colors the board is drawn with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Palette {
+    ///Numbers and lines
+    pub foreground: Color,
+    ///Behind what the tutorial says to click or drag
+    pub highlight: Color,
+    ///The row being dragged
+    pub selection: Color,
+    pub hint: Color,
+    ///Pivots, like the one a hint eliminates with
+    pub pivot: Color,
+    ///Behind coefficients that still have to be made 0
+    pub remaining: Color,
+    ///Around what a mistake changed
+    pub error: Color,
+    ///How many times thicker than normal to draw lines
+    pub line_width: f64,
+}
+impl Palette {
+    ///The palette for exported images, which always have a white background
+    pub fn export() -> Self {
+        ColorScheme::Standard.palette((0.0, 0.0, 0.0), false)
+    }
+    ///The palette for a widget, using its text color from the GTK theme to tell if the theme is
+    ///dark.
+    pub fn for_widget(scheme: ColorScheme, widget: &impl IsA<gtk4::Widget>) -> Self {
+        let color = widget.style_context().color();
+        let foreground = (
+            color.red() as f64,
+            color.green() as f64,
+            color.blue() as f64,
+        );
+        //Light text is on a dark background.
+        let dark = 0.2126 * foreground.0 + 0.7152 * foreground.1 + 0.0722 * foreground.2 > 0.5;
+        scheme.palette(foreground, dark)
+    }
+}
@@ -0,0 +1,195 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!Puzzle files have one equation per line with the coefficients separated by spaces, then a `|`,
+//!then the solution, like `2 -1 3/2 | 4`. Blank lines and lines starting with `#` are ignored.
+use super::*;
+use std::fmt;
+#[derive(Clone, Debug, PartialEq)]
+pub enum PuzzleError {
+    BadNumber(usize, String),
+    MissingBar(usize),
+    WrongLength(usize, usize, usize),
+    NotSquare(usize, usize),
+    Empty,
+    TooBig(usize),
+}
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::BadNumber(line, number) => {
+                write!(f, "line {}: {:?} is not a number", line, number)
+            }
+            Self::MissingBar(line) => write!(f, "line {}: no | before the solution", line),
+            Self::WrongLength(line, found, expected) => write!(
+                f,
+                "line {}: {} coefficients but the system needs {}",
+                line, found, expected
+            ),
+            Self::NotSquare(equations, unknowns) => write!(
+                f,
+                "{} equations with {} unknowns, but there must be as many equations as unknowns",
+                equations, unknowns
+            ),
+            Self::Empty => write!(f, "no equations"),
+            Self::TooBig(size) => write!(
+                f,
+                "{} equations is more than the maximum of {}",
+                size, MAX_SYSTEM_SIZE
+            ),
+        }
+    }
+}
+impl std::error::Error for PuzzleError {}
+fn parse_number(line: usize, number: &str) -> Result<f64, PuzzleError> {
+    let bad_number = || PuzzleError::BadNumber(line, number.to_string());
+    let parse_finite = |text: &str| match text.parse::<f64>() {
+        Ok(number) if number.is_finite() => Ok(number),
+        _ => Err(bad_number()),
+    };
+    let number = match number.split_once('/') {
+        Some((numerator, denominator)) => {
+            let numerator = parse_finite(numerator)?;
+            let denominator = parse_finite(denominator)?;
+            if denominator == 0.0 {
+                return Err(bad_number());
+            }
+            numerator / denominator
+        }
+        None => parse_finite(number)?,
+    };
+    //Dividing can still overflow, like 1e308/1e-308.
+    if number.is_finite() {
+        Ok(number)
+    } else {
+        Err(bad_number())
+    }
+}
+pub fn parse_system(text: &str) -> Result<System, PuzzleError> {
+    let mut equations: Vec<Equation> = Vec::new();
+    let lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+    for (line_number, line) in lines {
+        let (coefficients, solution) = line
+            .split_once('|')
+            .ok_or(PuzzleError::MissingBar(line_number))?;
+        let coefficients = coefficients
+            .split_whitespace()
+            .map(|number| parse_number(line_number, number))
+            .collect::<Result<Vec<f64>, PuzzleError>>()?;
+        let solution = parse_number(line_number, solution.trim())?;
+        if let Some(first) = equations.first()
+            && coefficients.len() != first.coefficients.len()
+        {
+            return Err(PuzzleError::WrongLength(
+                line_number,
+                coefficients.len(),
+                first.coefficients.len(),
+            ));
+        }
+        equations.push(Equation::new(coefficients, solution));
+    }
+    let Some(first) = equations.first() else {
+        return Err(PuzzleError::Empty);
+    };
+    //Every line has as many coefficients as the first, so checking the first is enough.
+    if first.coefficients.len() != equations.len() {
+        return Err(PuzzleError::NotSquare(
+            equations.len(),
+            first.coefficients.len(),
+        ));
+    }
+    if equations.len() > MAX_SYSTEM_SIZE {
+        return Err(PuzzleError::TooBig(equations.len()));
+    }
+    Ok(System::new(equations))
+}
+///Fractions are written exactly if they can be and other numbers with enough digits to get the
+///same `f64` back.
+fn format_puzzle_number(float: f64) -> String {
+    match approximate_fraction(float) {
+        Some((numerator, 1)) if numerator as f64 == float => numerator.to_string(),
+        Some((numerator, denominator)) if numerator as f64 / denominator as f64 == float => {
+            format!("{}/{}", numerator, denominator)
+        }
+        _ => float.to_string(),
+    }
+}
+pub fn format_system(system: &System) -> String {
+    let mut text = String::new();
+    for equation in system.equations.iter() {
+        for coefficient in equation.coefficients.iter() {
+            text.push_str(&format_puzzle_number(*coefficient));
+            text.push(' ');
+        }
+        text.push_str("| ");
+        text.push_str(&format_puzzle_number(equation.solution));
+        text.push('\n');
+    }
+    text
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(parse_number(1, "3"), Ok(3.0));
+        assert_eq!(parse_number(1, "-2.5"), Ok(-2.5));
+        assert_eq!(parse_number(1, "3/2"), Ok(1.5));
+        assert_eq!(parse_number(1, "-1/4"), Ok(-0.25));
+    }
+    #[test]
+    fn rejects_numbers_that_are_not_finite() {
+        for number in [
+            "x",
+            "",
+            "1/0",
+            "1/",
+            "inf",
+            "NaN",
+            "inf/1",
+            "nan/2",
+            "1/inf",
+            "1e308/1e-308",
+        ] {
+            assert_eq!(
+                parse_number(4, number),
+                Err(PuzzleError::BadNumber(4, number.to_string())),
+                "{}",
+                number
+            );
+        }
+    }
+    #[test]
+    fn parses_systems() {
+        let system = parse_system("# A comment\n2 -1 | 3/2\n\n1 1 | 4\n").unwrap();
+        assert_eq!(
+            system,
+            System::new(vec![
+                Equation::new(vec![2.0, -1.0], 1.5),
+                Equation::new(vec![1.0, 1.0], 4.0),
+            ])
+        );
+        assert_eq!(parse_system(&format_system(&system)), Ok(system));
+    }
+    #[test]
+    fn reports_bad_systems() {
+        assert_eq!(parse_system(""), Err(PuzzleError::Empty));
+        assert_eq!(parse_system("# Only a comment"), Err(PuzzleError::Empty));
+        assert_eq!(parse_system("1 2 3"), Err(PuzzleError::MissingBar(1)));
+        assert_eq!(
+            parse_system("1 2 | 3\n1 | 2"),
+            Err(PuzzleError::WrongLength(2, 1, 2))
+        );
+        assert_eq!(parse_system("1 2 | 3"), Err(PuzzleError::NotSquare(1, 2)));
+        assert_eq!(
+            parse_system("1 | 2\n\n1 | y"),
+            Err(PuzzleError::BadNumber(3, "y".to_string()))
+        );
+        let size = MAX_SYSTEM_SIZE + 1;
+        let too_big = format!("{}| 1\n", "1 ".repeat(size)).repeat(size);
+        assert_eq!(parse_system(&too_big), Err(PuzzleError::TooBig(size)));
+    }
+}
@@ -0,0 +1,335 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!Preferences kept between runs of the game in `settings.txt` in the user's config directory
+//!(`$XDG_CONFIG_HOME/gauss-elim-game` on Linux), changed in the settings window. Each line is a
+//!name and a value:
+//!```text
+//!size 4
+//!difficulty medium
+//!number-format fraction
+//!animations off
+//!theme dark
+//!colors colorblind
+//!mode time-attack
+//!```
+//!Options given on the command line take the place of the saved ones for that run only.
+use super::*;
+use gtk4::{Grid, SpinButton, Switch};
+///Whether the window is light or dark
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Theme {
+    ///Whatever the desktop uses
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+impl Theme {
+    pub const ALL: [Self; 3] = [Self::System, Self::Light, Self::Dark];
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::System => "System",
+            Self::Light => "Light",
+            Self::Dark => "Dark",
+        }
+    }
+    fn from_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.name().eq_ignore_ascii_case(id))
+    }
+    ///Tells GTK whether to use the light or dark version of its theme.
+    pub fn apply(self) {
+        let Some(settings) = gtk4::Settings::default() else {
+            return;
+        };
+        match self {
+            Self::System => settings.reset_property("gtk-application-prefer-dark-theme"),
+            Self::Light => settings.set_gtk_application_prefer_dark_theme(false),
+            Self::Dark => settings.set_gtk_application_prefer_dark_theme(true),
+        }
+    }
+}
+///Turns GTK's animations, like the toast sliding up, off or back to what the desktop uses.
+fn apply_animations(animations: bool) {
+    let Some(settings) = gtk4::Settings::default() else {
+        return;
+    };
+    if animations {
+        settings.reset_property("gtk-enable-animations");
+    } else {
+        settings.set_gtk_enable_animations(false);
+    }
+}
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    pub size: usize,
+    pub difficulty: Difficulty,
+    pub number_format: NumberFormat,
+    pub animations: bool,
+    pub theme: Theme,
+    pub colors: ColorScheme,
+    ///The mode started when the game opens
+    pub mode: GameMode,
+}
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            size: DEFAULT_SYSTEM_SIZE,
+            difficulty: Difficulty::default(),
+            number_format: NumberFormat::default(),
+            animations: true,
+            theme: Theme::default(),
+            colors: ColorScheme::default(),
+            mode: GameMode::default(),
+        }
+    }
+}
+fn settings_path() -> PathBuf {
+    glib::user_config_dir()
+        .join(env!("CARGO_PKG_NAME"))
+        .join("settings.txt")
+}
+impl Settings {
+    ///Reads the settings file, using the defaults for anything it doesn't have.
+    pub fn load() -> Self {
+        match std::fs::read_to_string(settings_path()) {
+            Ok(text) => Self::parse(&text),
+            Err(_) => Self::default(),
+        }
+    }
+    fn parse(text: &str) -> Self {
+        let mut settings = Self::default();
+        for line in text.lines() {
+            let Some((name, value)) = line.trim().split_once(' ') else {
+                continue;
+            };
+            let value = value.trim();
+            match name {
+                "size" => {
+                    if let Ok(size) = value.parse()
+                        && (1..=MAX_SYSTEM_SIZE).contains(&size)
+                    {
+                        settings.size = size;
+                    }
+                }
+                "difficulty" => {
+                    if let Ok(difficulty) = value.parse() {
+                        settings.difficulty = difficulty;
+                    }
+                }
+                "number-format" => {
+                    if let Ok(number_format) = value.parse() {
+                        settings.number_format = number_format;
+                    }
+                }
+                "animations" => settings.animations = value != "off",
+                "theme" => {
+                    if let Some(theme) = Theme::from_id(value) {
+                        settings.theme = theme;
+                    }
+                }
+                "colors" => {
+                    if let Some(colors) = ColorScheme::from_id(value) {
+                        settings.colors = colors;
+                    }
+                }
+                "mode" => {
+                    if let Some(mode) = GameMode::from_id(value) {
+                        settings.mode = mode;
+                    }
+                }
+                _ => {}
+            }
+        }
+        settings
+    }
+    fn to_text(&self) -> String {
+        format!(
+            "size {}\ndifficulty {}\nnumber-format {}\nanimations {}\ntheme {}\ncolors {}\nmode {}\n",
+            self.size,
+            self.difficulty.name().to_ascii_lowercase(),
+            self.number_format.name().to_ascii_lowercase(),
+            if self.animations { "on" } else { "off" },
+            self.theme.name().to_ascii_lowercase(),
+            self.colors.id(),
+            self.mode.id()
+        )
+    }
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = settings_path();
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        std::fs::write(path, self.to_text())
+    }
+    ///Applies the settings that are about the whole app rather than the game.
+    pub fn apply(&self) {
+        self.theme.apply();
+        apply_animations(self.animations);
+    }
+}
+///Opens a window for changing the settings, or brings it to the front if it's already open. It
+///starts out showing what the game is using, which can be from the command line rather than
+///saved. Each change is saved right away and used as soon as it can be: the number format, look,
+///and colors right away, the size and difficulty from the next new system, and the mode the next
+///time the game opens.
+pub fn show_settings_window(game: &Rc<Game>) {
+    if let Some(window) = game.settings_window.borrow().as_ref() {
+        window.present();
+        return;
+    }
+    //Only the setting that's changed is saved, so options from the command line stay for this
+    //run only.
+    let settings = Rc::new(RefCell::new(Settings::load()));
+    //Changes and saves the settings.
+    let my_settings = Rc::clone(&settings);
+    let change = Rc::new(move |update: &dyn Fn(&mut Settings)| {
+        let mut settings = my_settings.borrow_mut();
+        update(&mut settings);
+        if let Err(error) = settings.save() {
+            eprintln!("Could not save settings: {}", error);
+        }
+    });
+    let grid = Grid::builder()
+        .row_spacing(10)
+        .column_spacing(10)
+        .margin_top(10)
+        .margin_bottom(10)
+        .margin_start(10)
+        .margin_end(10)
+        .build();
+    let add_row = |row: i32, label: &str, widget: &gtk4::Widget| {
+        grid.attach(
+            &Label::builder()
+                .label(tr(label))
+                .halign(gtk4::Align::Start)
+                .build(),
+            0,
+            row,
+            1,
+            1,
+        );
+        grid.attach(widget, 1, row, 1, 1);
+    };
+    let size = SpinButton::with_range(1.0, MAX_SYSTEM_SIZE as f64, 1.0);
+    size.set_value(game.size.get() as f64);
+    let my_game = Rc::clone(game);
+    let my_change = Rc::clone(&change);
+    size.connect_value_changed(move |button| {
+        let size = button.value_as_int() as usize;
+        my_game.size.set(size);
+        my_change(&|settings| settings.size = size);
+    });
+    add_row(0, "Board size", size.upcast_ref());
+    let difficulties = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+    let difficulty = DropDown::from_strings(&difficulties.map(|difficulty| tr(difficulty.name())));
+    difficulty.set_selected(
+        difficulties
+            .iter()
+            .position(|difficulty| *difficulty == game.difficulty.get())
+            .unwrap_or(0) as u32,
+    );
+    let my_game = Rc::clone(game);
+    let my_change = Rc::clone(&change);
+    difficulty.connect_selected_notify(move |drop_down| {
+        let difficulty = difficulties[drop_down.selected() as usize];
+        my_game.difficulty.set(difficulty);
+        my_change(&|settings| settings.difficulty = difficulty);
+    });
+    add_row(1, "Difficulty", difficulty.upcast_ref());
+    let number_formats = [NumberFormat::Decimal, NumberFormat::Fraction];
+    let number_format =
+        DropDown::from_strings(&number_formats.map(|number_format| tr(number_format.name())));
+    number_format.set_selected(
+        number_formats
+            .iter()
+            .position(|number_format| *number_format == game.number_format.get())
+            .unwrap_or(0) as u32,
+    );
+    let my_game = Rc::clone(game);
+    let my_change = Rc::clone(&change);
+    number_format.connect_selected_notify(move |drop_down| {
+        let number_format = number_formats[drop_down.selected() as usize];
+        my_game.number_format.set(number_format);
+        my_game.redraw_all();
+        my_change(&|settings| settings.number_format = number_format);
+    });
+    add_row(2, "Numbers", number_format.upcast_ref());
+    let animations = Switch::builder()
+        .active(settings.borrow().animations)
+        .halign(gtk4::Align::Start)
+        .build();
+    let my_change = Rc::clone(&change);
+    animations.connect_active_notify(move |switch| {
+        let animations = switch.is_active();
+        apply_animations(animations);
+        my_change(&|settings| settings.animations = animations);
+    });
+    add_row(3, "Animations", animations.upcast_ref());
+    let theme = DropDown::from_strings(&Theme::ALL.map(|theme| tr(theme.name())));
+    theme.set_selected(
+        Theme::ALL
+            .iter()
+            .position(|theme| *theme == settings.borrow().theme)
+            .unwrap_or(0) as u32,
+    );
+    let my_game = Rc::clone(game);
+    let my_change = Rc::clone(&change);
+    theme.connect_selected_notify(move |drop_down| {
+        let theme = Theme::ALL[drop_down.selected() as usize];
+        theme.apply();
+        my_game.redraw_all();
+        my_change(&|settings| settings.theme = theme);
+    });
+    add_row(4, "Theme", theme.upcast_ref());
+    let colors = DropDown::from_strings(&ColorScheme::ALL.map(|colors| tr(colors.name())));
+    colors.set_selected(
+        ColorScheme::ALL
+            .iter()
+            .position(|colors| *colors == game.color_scheme.get())
+            .unwrap_or(0) as u32,
+    );
+    let my_game = Rc::clone(game);
+    let my_change = Rc::clone(&change);
+    colors.connect_selected_notify(move |drop_down| {
+        let colors = ColorScheme::ALL[drop_down.selected() as usize];
+        my_game.color_scheme.set(colors);
+        my_game.redraw_all();
+        my_change(&|settings| settings.colors = colors);
+    });
+    add_row(5, "Colors", colors.upcast_ref());
+    let mode = DropDown::from_strings(&GameMode::ALL.map(|mode| tr(mode.name())));
+    mode.set_selected(
+        GameMode::ALL
+            .iter()
+            .position(|mode| *mode == settings.borrow().mode)
+            .unwrap_or(0) as u32,
+    );
+    let my_change = Rc::clone(&change);
+    mode.connect_selected_notify(move |drop_down| {
+        let mode = GameMode::ALL[drop_down.selected() as usize];
+        my_change(&|settings| settings.mode = mode);
+    });
+    add_row(6, "Mode at start", mode.upcast_ref());
+    let note = Label::builder()
+        .label(tr(
+            "The board size and difficulty are used from the next new system.",
+        ))
+        .wrap(true)
+        .max_width_chars(40)
+        .build();
+    grid.attach(&note, 0, 7, 2, 1);
+    let window = gtk4::Window::builder()
+        .title(tr("Settings"))
+        .child(&grid)
+        .build();
+    window.set_transient_for(game.drawing_area.root().and_downcast_ref::<gtk4::Window>());
+    let my_game = Rc::clone(game);
+    window.connect_close_request(move |_| {
+        my_game.settings_window.borrow_mut().take();
+        glib::Propagation::Proceed
+    });
+    *game.settings_window.borrow_mut() = Some(window.clone());
+    window.present();
+}
@@ -0,0 +1,234 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!`gauss-elim-game solve [FILE] [--json] [--number-format decimal|fraction]
+//![--strategy naive|partial|complete]` prints the row operations that solve the system in a puzzle
+//!file, or standard input if there's no file or it's `-`, without opening a window. The output is
+//!in English with decimal points whatever the locale, so that other programs can read it.
+use super::*;
+use std::ffi::OsString;
+use std::io::Read;
+const USAGE: &str = "Usage: gauss-elim-game solve [FILE] [--json] [--number-format decimal|fraction] [--strategy naive|partial|complete]";
+fn text_matrix(system: &System, number_format: NumberFormat) -> String {
+    let rows: Vec<Vec<String>> = system
+        .equations
+        .iter()
+        .map(|equation| {
+            equation
+                .coefficients
+                .iter()
+                .chain(std::iter::once(&equation.solution))
+                .map(|number| format_number_with_point(*number, number_format))
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = (0..=system.size())
+        .map(|j| {
+            rows.iter()
+                .map(|row| row[j].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let mut matrix = String::new();
+    for row in rows {
+        matrix.push('[');
+        for (j, number) in row.iter().enumerate() {
+            if j == system.size() {
+                matrix.push_str(" |");
+            }
+            matrix.push_str(&format!(" {:>1$}", number, widths[j]));
+        }
+        matrix.push_str(" ]\n");
+    }
+    matrix
+}
+fn json_string(string: &str) -> String {
+    let mut json = String::from('"');
+    for character in string.chars() {
+        match character {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            character if character.is_control() => {
+                json.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => json.push(character),
+        }
+    }
+    json.push('"');
+    json
+}
+///Each row is the coefficients followed by the solution.
+fn json_matrix(system: &System) -> String {
+    let rows: Vec<String> = system
+        .equations
+        .iter()
+        .map(|equation| {
+            let numbers: Vec<String> = equation
+                .coefficients
+                .iter()
+                .chain(std::iter::once(&equation.solution))
+                .map(|number| number.to_string())
+                .collect();
+            format!("[{}]", numbers.join(", "))
+        })
+        .collect();
+    format!("[{}]", rows.join(", "))
+}
+fn json_operation(operation: RowOperation) -> String {
+    match operation {
+        RowOperation::SwitchRows(a, b) => {
+            format!("{{\"type\": \"switch_rows\", \"rows\": [{}, {}]}}", a, b)
+        }
+        RowOperation::MakeCoefficient1(equation, coefficient) => format!(
+            "{{\"type\": \"make_coefficient_1\", \"equation\": {}, \"coefficient\": {}}}",
+            equation, coefficient
+        ),
+        RowOperation::MakeCoefficient0WithRow(equation, coefficient, with) => format!(
+            "{{\"type\": \"make_coefficient_0_with_row\", \"equation\": {}, \"coefficient\": {}, \"with\": {}}}",
+            equation, coefficient, with
+        ),
+    }
+}
+fn print_text(start: &System, operations: &[RowOperation], number_format: NumberFormat) {
+    let mut system = start.clone();
+    println!("Start");
+    print!("{}", text_matrix(&system, number_format));
+    for (i, operation) in operations.iter().enumerate() {
+        println!();
+        println!(
+            "{}. {}",
+            i + 1,
+            describe_operation_with_point(&system, *operation, number_format)
+        );
+        system.apply(*operation);
+        print!("{}", text_matrix(&system, number_format));
+    }
+    println!();
+    match system.solution() {
+        Some(solution) => {
+            let values: Vec<String> = solution
+                .iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    format!(
+                        "x{} = {}",
+                        i + 1,
+                        format_number_with_point(*value, number_format)
+                    )
+                })
+                .collect();
+            println!("Solution: {}", values.join(", "));
+        }
+        None if system.is_inconsistent() => println!("No solution"),
+        None => println!("Infinitely many solutions"),
+    }
+}
+fn print_json(start: &System, operations: &[RowOperation], number_format: NumberFormat) {
+    let mut system = start.clone();
+    let mut steps = Vec::new();
+    for operation in operations {
+        let description = describe_operation_with_point(&system, *operation, number_format);
+        system.apply(*operation);
+        steps.push(format!(
+            "{{\"operation\": {}, \"description\": {}, \"matrix\": {}}}",
+            json_operation(*operation),
+            json_string(&description),
+            json_matrix(&system)
+        ));
+    }
+    let solution = match system.solution() {
+        Some(solution) => {
+            let values: Vec<String> = solution.iter().map(|value| value.to_string()).collect();
+            format!("[{}]", values.join(", "))
+        }
+        None => "null".to_string(),
+    };
+    println!("{{");
+    println!("  \"start\": {},", json_matrix(start));
+    println!("  \"steps\": [");
+    for (i, step) in steps.iter().enumerate() {
+        let comma = if i + 1 < steps.len() { "," } else { "" };
+        println!("    {}{}", step, comma);
+    }
+    println!("  ],");
+    println!("  \"solution\": {},", solution);
+    println!("  \"consistent\": {}", !system.is_inconsistent());
+    println!("}}");
+}
+pub fn run_solve_command(mut args: impl Iterator<Item = OsString>) -> glib::ExitCode {
+    let mut path = None;
+    let mut json = false;
+    let mut number_format = NumberFormat::default();
+    let mut strategy = PivotStrategy::default();
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("--json") => json = true,
+            Some("--number-format") => match args.next().as_ref().and_then(|arg| arg.to_str()) {
+                Some(format) => match format.parse() {
+                    Ok(format) => number_format = format,
+                    Err(error) => {
+                        eprintln!("{}", error);
+                        return glib::ExitCode::FAILURE;
+                    }
+                },
+                None => {
+                    eprintln!("--number-format needs decimal or fraction");
+                    return glib::ExitCode::FAILURE;
+                }
+            },
+            Some("--strategy") => match args.next().as_ref().and_then(|arg| arg.to_str()) {
+                Some(name) => match name.parse() {
+                    Ok(name) => strategy = name,
+                    Err(error) => {
+                        eprintln!("{}", error);
+                        return glib::ExitCode::FAILURE;
+                    }
+                },
+                None => {
+                    eprintln!("--strategy needs naive, partial, or complete");
+                    return glib::ExitCode::FAILURE;
+                }
+            },
+            Some("-h" | "--help") => {
+                println!("{}", USAGE);
+                return glib::ExitCode::SUCCESS;
+            }
+            Some(flag) if flag.starts_with('-') && flag != "-" => {
+                eprintln!("Unknown option {}\n{}", flag, USAGE);
+                return glib::ExitCode::FAILURE;
+            }
+            _ if path.is_some() => {
+                eprintln!("Only one file can be solved at a time\n{}", USAGE);
+                return glib::ExitCode::FAILURE;
+            }
+            _ => path = Some(PathBuf::from(arg)),
+        }
+    }
+    let text = match path.filter(|path| path.as_os_str() != "-") {
+        Some(path) => std::fs::read_to_string(&path)
+            .map_err(|error| format!("Could not read {}: {}", path.display(), error)),
+        None => {
+            let mut text = String::new();
+            std::io::stdin()
+                .read_to_string(&mut text)
+                .map(|_| text)
+                .map_err(|error| format!("Could not read standard input: {}", error))
+        }
+    };
+    let system = match text.and_then(|text| parse_system(&text).map_err(|error| error.to_string()))
+    {
+        Ok(system) => system,
+        Err(error) => {
+            eprintln!("{}", error);
+            return glib::ExitCode::FAILURE;
+        }
+    };
+    let operations = system.solve_with(strategy);
+    if json {
+        print_json(&system, &operations, number_format);
+    } else {
+        print_text(&system, &operations, number_format);
+    }
+    glib::ExitCode::SUCCESS
+}
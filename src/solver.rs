@@ -0,0 +1,382 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+use super::*;
+use rand::Rng;
+use std::collections::VecDeque;
+use std::str::FromStr;
+///How the solver picks each pivot
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PivotStrategy {
+    ///Go column by column and use the first nonzero entry, like doing it by hand
+    #[default]
+    Naive,
+    ///Go column by column and use the entry with the biggest absolute value
+    Partial,
+    ///Use the entry with the biggest absolute value out of every column that doesn't have a pivot
+    ///yet
+    Complete,
+}
+impl PivotStrategy {
+    pub const ALL: [Self; 3] = [Self::Naive, Self::Partial, Self::Complete];
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Naive => "Naive",
+            Self::Partial => "Partial pivoting",
+            Self::Complete => "Complete pivoting",
+        }
+    }
+    ///Picks the `(row, column)` of the next pivot from rows `first_row` and below and the columns
+    ///that aren't used yet.
+    fn choose_pivot(
+        self,
+        system: &System,
+        first_row: usize,
+        used_columns: &[bool],
+    ) -> Option<(usize, usize)> {
+        let size = system.size();
+        let entry = |row: usize, column: usize| system.equations[row].coefficients[column];
+        let mut columns = (0..size).filter(|&column| !used_columns[column]);
+        match self {
+            Self::Naive => columns.find_map(|column| {
+                (first_row..size)
+                    .find(|&row| entry(row, column) != 0.0)
+                    .map(|row| (row, column))
+            }),
+            Self::Partial => columns.find_map(|column| {
+                let mut best = None;
+                for row in first_row..size {
+                    //Only switch for something strictly bigger so that ties keep the current row.
+                    if entry(row, column) != 0.0
+                        && best
+                            .is_none_or(|best| entry(row, column).abs() > entry(best, column).abs())
+                    {
+                        best = Some(row);
+                    }
+                }
+                best.map(|row| (row, column))
+            }),
+            Self::Complete => {
+                let mut best: Option<(usize, usize)> = None;
+                for column in columns {
+                    for row in first_row..size {
+                        if entry(row, column) != 0.0
+                            && best.is_none_or(|(best_row, best_column)| {
+                                entry(row, column).abs() > entry(best_row, best_column).abs()
+                            })
+                        {
+                            best = Some((row, column));
+                        }
+                    }
+                }
+                best
+            }
+        }
+    }
+}
+impl FromStr for PivotStrategy {
+    type Err = String;
+    fn from_str(string: &str) -> Result<Self, String> {
+        match string.to_ascii_lowercase().as_str() {
+            "naive" => Ok(Self::Naive),
+            "partial" => Ok(Self::Partial),
+            "complete" => Ok(Self::Complete),
+            _ => Err(format!(
+                "unknown strategy {:?}, expected naive, partial, or complete",
+                string
+            )),
+        }
+    }
+}
+///Gives the solver's next operation one at a time for auto-play. The naive strategy just asks for
+///a hint each time. The others follow a plan from `solve_with` that is made again whenever the
+///system has been changed some other way.
+pub struct AutoPlayer {
+    strategy: PivotStrategy,
+    plan: VecDeque<RowOperation>,
+    ///What the system should be if nobody else has touched it since the last operation
+    expected: Option<System>,
+}
+impl AutoPlayer {
+    pub const fn new(strategy: PivotStrategy) -> Self {
+        Self {
+            strategy,
+            plan: VecDeque::new(),
+            expected: None,
+        }
+    }
+    pub fn next_operation(&mut self, system: &System) -> Option<RowOperation> {
+        if self.strategy == PivotStrategy::Naive {
+            return system.hint();
+        }
+        if self.expected.as_ref() != Some(system) {
+            self.plan = system.solve_with(self.strategy).into();
+            self.expected = Some(system.clone());
+        }
+        let operation = self.plan.pop_front()?;
+        if let Some(expected) = self.expected.as_mut() {
+            expected.apply(operation);
+        }
+        Some(operation)
+    }
+}
+fn do_operation<F: Field>(
+    system: &mut System<F>,
+    operations: &mut Vec<RowOperation>,
+    operation: RowOperation,
+) {
+    system.apply(operation);
+    operations.push(operation);
+}
+impl<F: Field> System<F> {
+    ///Finds row operations that take the system to reduced row echelon form with the naive
+    ///strategy by following hints until there aren't any.
+    pub fn solve(&self) -> Vec<RowOperation> {
+        let mut system = self.clone();
+        let mut operations = Vec::new();
+        while let Some(operation) = system.hint() {
+            do_operation(&mut system, &mut operations, operation);
+        }
+        operations
+    }
+    ///Checks if solving the system ends with the identity matrix.
+    pub fn has_unique_solution(&self) -> bool {
+        self.unique_solution().is_some()
+    }
+    ///Solves a copy of the system and returns the value of each unknown if there's only one
+    ///answer.
+    pub fn unique_solution(&self) -> Option<Vec<F>> {
+        let mut system = self.clone();
+        for operation in self.solve() {
+            system.apply(operation);
+        }
+        system.solution()
+    }
+}
+impl System {
+    ///Finds row operations that take the system to reduced row echelon form. The naive strategy
+    ///is the same as `solve`. For the others, each pivot is chosen with `strategy` and cleared
+    ///below, then each pivot is made 1 and cleared above from the bottom up. Complete pivoting can
+    ///put pivots out of order, so its rows are switched back into order at the end.
+    pub fn solve_with(&self, strategy: PivotStrategy) -> Vec<RowOperation> {
+        if strategy == PivotStrategy::Naive {
+            return self.solve();
+        }
+        let size = self.size();
+        let mut system = self.clone();
+        let mut operations = Vec::new();
+        let mut used_columns = vec![false; size];
+        //(row, column) of each pivot, in order from top to bottom
+        let mut pivots: Vec<(usize, usize)> = Vec::new();
+        for row in 0..size {
+            let Some((pivot_row, column)) = strategy.choose_pivot(&system, row, &used_columns)
+            else {
+                break;
+            };
+            if pivot_row != row {
+                do_operation(
+                    &mut system,
+                    &mut operations,
+                    RowOperation::SwitchRows(row, pivot_row),
+                );
+            }
+            for i in row + 1..size {
+                if system.should_make_coefficient_0_with_row(i, column, row) {
+                    do_operation(
+                        &mut system,
+                        &mut operations,
+                        RowOperation::MakeCoefficient0WithRow(i, column, row),
+                    );
+                }
+            }
+            used_columns[column] = true;
+            pivots.push((row, column));
+        }
+        for &(row, column) in pivots.iter().rev() {
+            if system.should_make_coefficient_1(row, column) {
+                do_operation(
+                    &mut system,
+                    &mut operations,
+                    RowOperation::MakeCoefficient1(row, column),
+                );
+            }
+            for i in 0..row {
+                if system.should_make_coefficient_0_with_row(i, column, row) {
+                    do_operation(
+                        &mut system,
+                        &mut operations,
+                        RowOperation::MakeCoefficient0WithRow(i, column, row),
+                    );
+                }
+            }
+        }
+        pivots.sort_by_key(|&(_, column)| column);
+        for target in 0..pivots.len() {
+            let row = pivots[target].0;
+            if row != target {
+                do_operation(
+                    &mut system,
+                    &mut operations,
+                    RowOperation::SwitchRows(target, row),
+                );
+                //Whichever pivot was in the target row is now where this one was.
+                for pivot in pivots.iter_mut() {
+                    if pivot.0 == target {
+                        pivot.0 = row;
+                    }
+                }
+                pivots[target].0 = target;
+            }
+        }
+        //If there are columns without pivots, complete pivoting might have skipped over the
+        //column that should have had the pivot, which leaves a leading coefficient that isn't 1.
+        //Going through it again column by column fixes that.
+        if strategy == PivotStrategy::Complete && pivots.len() < size {
+            for operation in system.solve() {
+                do_operation(&mut system, &mut operations, operation);
+            }
+        }
+        operations
+    }
+    ///Makes a system where the naive strategy has to use a tiny first pivot and partial
+    ///pivoting picks a pivot from an equation with much bigger numbers in its other coefficients,
+    ///so floating point error builds up differently for each strategy. Also returns the whole
+    ///number answer it was made from.
+    pub fn ill_conditioned(size: usize, rng: &mut impl Rng) -> (Self, Vec<f64>) {
+        const TINY: f64 = 1e-12;
+        const SCALE: f64 = 1e10;
+        let answers: Vec<f64> = (0..size).map(|_| rng.random_range(-5..=5) as f64).collect();
+        let equations = (0..size)
+            .map(|i| {
+                let mut coefficients: Vec<f64> = (0..size)
+                    .map(|_| {
+                        let sign = if rng.random() { 1.0 } else { -1.0 };
+                        rng.random_range(1..=9) as f64 * sign
+                    })
+                    .collect();
+                match i {
+                    0 => coefficients[0] = TINY,
+                    //Bigger than anything else in the first column so partial pivoting picks it
+                    1 => {
+                        coefficients[0] = 10.0;
+                        for coefficient in coefficients.iter_mut().skip(1) {
+                            *coefficient *= SCALE;
+                        }
+                    }
+                    _ => {}
+                }
+                let solution = coefficients.iter().zip(&answers).map(|(a, x)| a * x).sum();
+                Equation::new(coefficients, solution)
+            })
+            .collect();
+        (Self::new(equations), answers)
+    }
+    ///The biggest difference between the two sides of any equation if the unknowns were `answers`
+    pub fn residual(&self, answers: &[f64]) -> f64 {
+        self.equations
+            .iter()
+            .map(|equation| {
+                let left: f64 = equation
+                    .coefficients
+                    .iter()
+                    .zip(answers)
+                    .map(|(a, x)| a * x)
+                    .sum();
+                (left - equation.solution).abs()
+            })
+            .fold(0.0, f64::max)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn solved(system: &System, operations: &[RowOperation]) -> System {
+        let mut system = system.clone();
+        for operation in operations {
+            system.apply(*operation);
+        }
+        system
+    }
+    #[test]
+    fn every_strategy_reaches_reduced_row_echelon_form() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut systems: Vec<System> = (2..=5)
+            .flat_map(|size| {
+                [Difficulty::Easy, Difficulty::Hard]
+                    .map(|difficulty| System::random(size, difficulty, &mut rng))
+            })
+            .collect();
+        //No solution, infinitely many, and a column with no pivot before one with a pivot
+        systems.push(System::new(vec![
+            Equation::new(vec![1.0, 2.0], 3.0),
+            Equation::new(vec![2.0, 4.0], 5.0),
+        ]));
+        systems.push(System::new(vec![
+            Equation::new(vec![1.0, 2.0, 3.0], 6.0),
+            Equation::new(vec![2.0, 4.0, 6.0], 12.0),
+            Equation::new(vec![0.0, 1.0, 1.0], 2.0),
+        ]));
+        systems.push(System::new(vec![
+            Equation::new(vec![0.0, 1.0, 2.0], 1.0),
+            Equation::new(vec![0.0, 3.0, 1.0], 2.0),
+            Equation::new(vec![0.0, 2.0, 9.0], 3.0),
+        ]));
+        for system in systems {
+            for strategy in PivotStrategy::ALL {
+                let operations = system.solve_with(strategy);
+                assert!(
+                    solved(&system, &operations).is_reduced_row_echelon_form(),
+                    "{:?} with {:?}",
+                    system,
+                    strategy
+                );
+            }
+        }
+    }
+    #[test]
+    fn partial_pivoting_switches_to_the_biggest_entry() {
+        let system = System::new(vec![
+            Equation::new(vec![1.0, 2.0], 5.0),
+            Equation::new(vec![3.0, 4.0], 11.0),
+        ]);
+        assert_eq!(system.solve_with(PivotStrategy::Naive), system.solve());
+        assert_eq!(
+            system.solve_with(PivotStrategy::Partial)[0],
+            RowOperation::SwitchRows(0, 1)
+        );
+        assert_eq!(
+            PivotStrategy::Complete.choose_pivot(&system, 0, &[false, false]),
+            Some((1, 1))
+        );
+        assert_eq!(
+            solved(&system, &system.solve_with(PivotStrategy::Complete)).solution(),
+            Some(vec![1.0, 2.0])
+        );
+    }
+    #[test]
+    fn pivoting_reduces_rounding_error() {
+        for seed in 0..10 {
+            let (system, answers) = System::ill_conditioned(3, &mut StdRng::seed_from_u64(seed));
+            let error = |strategy| {
+                let solution = solved(&system, &system.solve_with(strategy))
+                    .solution()
+                    .unwrap();
+                solution
+                    .iter()
+                    .zip(&answers)
+                    .map(|(x, answer)| (x - answer).abs())
+                    .fold(0.0, f64::max)
+            };
+            assert!(
+                error(PivotStrategy::Partial) < error(PivotStrategy::Naive),
+                "seed {}",
+                seed
+            );
+        }
+    }
+    #[test]
+    fn parses_strategies() {
+        assert_eq!("Partial".parse(), Ok(PivotStrategy::Partial));
+        assert_eq!("complete".parse(), Ok(PivotStrategy::Complete));
+        assert!("best".parse::<PivotStrategy>().is_err());
+    }
+}
@@ -0,0 +1,377 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!Statistics kept between runs of the game in `stats.txt` in the user's data directory
+//!(`$XDG_DATA_HOME/gauss-elim-game` on Linux). Each line is a name followed by space separated
+//!values:
+//!```text
+//!size 4 medium solved 12 moves 150 par 140 seconds 620.5 best 30.2 hints 3
+//!hints 5
+//!daily streak 3 best 7 last 20345
+//!mistakes refilled 2 swaps 1 scalings 0
+//!achievement first-solve
+//!level 1
+//!```
+//!Lines that can't be read are skipped so that a damaged file only loses what's damaged.
+use super::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+///Totals for systems of one size and difficulty
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SizeStats {
+    pub solved: u64,
+    pub moves: u64,
+    ///The total of how many operations the solver would have needed
+    pub par: u64,
+    pub seconds: f64,
+    ///The fastest solve
+    pub best_seconds: Option<f64>,
+    ///Hints used on systems that were then solved
+    pub hints: u64,
+}
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub by_size: BTreeMap<(usize, Difficulty), SizeStats>,
+    ///Every hint ever asked for, solved or not
+    pub hints_used: u64,
+    ///Days in a row the daily puzzle has been solved, up to the last one
+    pub daily_streak: u64,
+    pub best_daily_streak: u64,
+    ///The day of the last solved daily puzzle
+    pub last_daily: Option<i64>,
+    pub achievements: BTreeSet<Achievement>,
+    ///Indices of the campaign levels that are complete, counted from 0 but saved counting from 1
+    pub completed_levels: BTreeSet<usize>,
+    ///Times a cleared column was filled back in
+    pub refilled_columns: u64,
+    pub undone_swaps: u64,
+    pub undone_scalings: u64,
+}
+fn stats_path() -> PathBuf {
+    glib::user_data_dir()
+        .join(env!("CARGO_PKG_NAME"))
+        .join("stats.txt")
+}
+///Reads `name value` pairs from the rest of a line. Every value is a count, a time, or a day, so
+///values that are negative, infinite, or NaN make the line unreadable.
+fn parse_pairs<'a>(mut words: impl Iterator<Item = &'a str>) -> Option<BTreeMap<&'a str, f64>> {
+    let mut pairs = BTreeMap::new();
+    while let Some(name) = words.next() {
+        let value: f64 = words.next()?.parse().ok()?;
+        if !value.is_finite() || value < 0.0 {
+            return None;
+        }
+        pairs.insert(name, value);
+    }
+    Some(pairs)
+}
+fn parse_size_line<'a>(
+    mut words: impl Iterator<Item = &'a str>,
+) -> Option<(usize, Difficulty, SizeStats)> {
+    let size = words.next()?.parse().ok()?;
+    let difficulty = words.next()?.parse().ok()?;
+    let pairs = parse_pairs(words)?;
+    let get = |name| pairs.get(name).copied().unwrap_or(0.0);
+    let stats = SizeStats {
+        solved: get("solved") as u64,
+        moves: get("moves") as u64,
+        par: get("par") as u64,
+        seconds: get("seconds"),
+        best_seconds: pairs.get("best").copied(),
+        hints: get("hints") as u64,
+    };
+    Some((size, difficulty, stats))
+}
+impl Stats {
+    ///Reads the statistics file, starting from nothing if there isn't one.
+    pub fn load() -> Self {
+        match std::fs::read_to_string(stats_path()) {
+            Ok(text) => Self::parse(&text),
+            Err(_) => Self::default(),
+        }
+    }
+    fn parse(text: &str) -> Self {
+        let mut stats = Self::default();
+        for line in text.lines() {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("size") => {
+                    if let Some((size, difficulty, size_stats)) = parse_size_line(words) {
+                        stats.by_size.insert((size, difficulty), size_stats);
+                    }
+                }
+                Some("hints") => {
+                    if let Some(Ok(hints)) = words.next().map(str::parse) {
+                        stats.hints_used = hints;
+                    }
+                }
+                Some("daily") => {
+                    if let Some(pairs) = parse_pairs(words) {
+                        stats.daily_streak = pairs.get("streak").copied().unwrap_or(0.0) as u64;
+                        stats.best_daily_streak = pairs.get("best").copied().unwrap_or(0.0) as u64;
+                        stats.last_daily = pairs.get("last").map(|last| *last as i64);
+                    }
+                }
+                Some("mistakes") => {
+                    if let Some(pairs) = parse_pairs(words) {
+                        let get = |name| pairs.get(name).copied().unwrap_or(0.0) as u64;
+                        stats.refilled_columns = get("refilled");
+                        stats.undone_swaps = get("swaps");
+                        stats.undone_scalings = get("scalings");
+                    }
+                }
+                Some("achievement") => {
+                    if let Some(achievement) = words.next().and_then(Achievement::from_id) {
+                        stats.achievements.insert(achievement);
+                    }
+                }
+                Some("level") => {
+                    if let Some(Ok(level)) = words.next().map(str::parse::<usize>)
+                        && level > 0
+                    {
+                        stats.completed_levels.insert(level - 1);
+                    }
+                }
+                _ => {}
+            }
+        }
+        stats
+    }
+    fn to_text(&self) -> String {
+        let mut text = String::new();
+        for ((size, difficulty), size_stats) in self.by_size.iter() {
+            text.push_str(&format!(
+                "size {} {} solved {} moves {} par {} seconds {} hints {}",
+                size,
+                difficulty.name().to_ascii_lowercase(),
+                size_stats.solved,
+                size_stats.moves,
+                size_stats.par,
+                size_stats.seconds,
+                size_stats.hints
+            ));
+            if let Some(best) = size_stats.best_seconds {
+                text.push_str(&format!(" best {}", best));
+            }
+            text.push('\n');
+        }
+        text.push_str(&format!("hints {}\n", self.hints_used));
+        text.push_str(&format!(
+            "daily streak {} best {}",
+            self.daily_streak, self.best_daily_streak
+        ));
+        if let Some(last) = self.last_daily {
+            text.push_str(&format!(" last {}", last));
+        }
+        text.push('\n');
+        text.push_str(&format!(
+            "mistakes refilled {} swaps {} scalings {}\n",
+            self.refilled_columns, self.undone_swaps, self.undone_scalings
+        ));
+        for achievement in self.achievements.iter() {
+            text.push_str(&format!("achievement {}\n", achievement.id()));
+        }
+        for level in self.completed_levels.iter() {
+            text.push_str(&format!("level {}\n", level + 1));
+        }
+        text
+    }
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = stats_path();
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        std::fs::write(path, self.to_text())
+    }
+    pub fn record_solve(
+        &mut self,
+        size: usize,
+        difficulty: Difficulty,
+        moves: usize,
+        par: usize,
+        seconds: f64,
+        hints: u64,
+    ) {
+        let size_stats = self.by_size.entry((size, difficulty)).or_default();
+        size_stats.solved += 1;
+        size_stats.moves += moves as u64;
+        size_stats.par += par as u64;
+        size_stats.seconds += seconds;
+        size_stats.hints += hints;
+        if size_stats.best_seconds.is_none_or(|best| seconds < best) {
+            size_stats.best_seconds = Some(seconds);
+        }
+    }
+    pub fn record_mistake(&mut self, mistake: Mistake) {
+        match mistake {
+            Mistake::RefilledColumn(_) => self.refilled_columns += 1,
+            Mistake::UndidSwap => self.undone_swaps += 1,
+            Mistake::UndidScaling => self.undone_scalings += 1,
+        }
+    }
+    ///Describes the statistics for the Statistics tab.
+    pub fn describe(&self) -> String {
+        let mut lines = Vec::new();
+        if self.by_size.is_empty() {
+            lines.push(tr("No systems solved yet.").to_string());
+        }
+        for ((size, difficulty), size_stats) in self.by_size.iter() {
+            let solved = size_stats.solved.max(1) as f64;
+            let mut line = tr_format(
+                "{}×{} {}: {} solved, {} moves on average (par {}), {} on average",
+                &[
+                    &size,
+                    &size,
+                    &tr(difficulty.name()),
+                    &size_stats.solved,
+                    &localize_decimal(format!("{:.1}", size_stats.moves as f64 / solved)),
+                    &localize_decimal(format!("{:.1}", size_stats.par as f64 / solved)),
+                    &format_duration(Duration::from_secs_f64(size_stats.seconds / solved)),
+                ],
+            );
+            if let Some(best) = size_stats.best_seconds {
+                line.push_str(&tr_format(
+                    ", best {}",
+                    &[&format_duration(Duration::from_secs_f64(best))],
+                ));
+            }
+            line.push_str(&tr_format(", hints used {}", &[&size_stats.hints]));
+            lines.push(line);
+        }
+        lines.push(String::new());
+        lines.push(tr_format("Hints used: {}", &[&self.hints_used]));
+        lines.push(tr_format(
+            "Mistakes: cleared columns filled back in {}, swaps undone {}, scalings undone {}",
+            &[
+                &self.refilled_columns,
+                &self.undone_swaps,
+                &self.undone_scalings,
+            ],
+        ));
+        lines.push(tr_format(
+            "Daily puzzle streak: {} (best {})",
+            &[&self.current_daily_streak(), &self.best_daily_streak],
+        ));
+        lines.join("\n")
+    }
+}
+impl Game {
+    ///Adds the system that was just solved to the statistics and saves them. Systems the
+    ///computer helped solve in the solver window, the tutorial, and systems that aren't real don't
+    ///count.
+    pub(crate) fn record_solve(&self) {
+        let mut record = SolveRecord {
+            assisted: self.assisted.get() || self.tutorial_step.get().is_some(),
+            real: self.field_system.borrow().is_none(),
+            size: self.system.borrow().size(),
+            moves: self.operations.borrow().len(),
+            par: 0,
+            hints: self.puzzle_hints.get(),
+            fraction_seen: self.fraction_seen.get(),
+            only_add_multiple: only_adds_multiples(&self.operations.borrow()),
+            daily_streak: 0,
+        };
+        if !record.counts() {
+            return;
+        }
+        record.par = self.start_system.borrow().solve().len();
+        let mut stats = self.stats.borrow_mut();
+        stats.record_solve(
+            record.size,
+            self.difficulty.get(),
+            record.moves,
+            record.par,
+            self.puzzle_started.get().elapsed().as_secs_f64(),
+            record.hints,
+        );
+        if let Some(day) = self.daily.get() {
+            stats.record_daily(day);
+        }
+        record.daily_streak = stats.current_daily_streak();
+        self.unlock_achievements(&mut stats, &record);
+        if let Err(error) = stats.save() {
+            eprintln!("Could not save statistics: {}", error);
+        }
+    }
+    ///Counts a hint toward the statistics.
+    pub fn record_hint(&self) {
+        self.puzzle_hints.set(self.puzzle_hints.get() + 1);
+        let mut stats = self.stats.borrow_mut();
+        stats.hints_used += 1;
+        if let Err(error) = stats.save() {
+            eprintln!("Could not save statistics: {}", error);
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn reads_what_it_writes() {
+        let mut stats = Stats::default();
+        stats.record_solve(3, Difficulty::Hard, 12, 10, 42.5, 1);
+        stats.record_solve(3, Difficulty::Hard, 8, 10, 30.25, 0);
+        stats.record_solve(2, Difficulty::Easy, 4, 4, 5.0, 0);
+        stats.hints_used = 7;
+        stats.record_daily(100);
+        stats.record_mistake(Mistake::UndidSwap);
+        stats.achievements.insert(Achievement::ALL[0]);
+        stats.completed_levels.insert(0);
+        stats.completed_levels.insert(4);
+        assert_eq!(Stats::parse(&stats.to_text()), stats);
+        let hard = &stats.by_size[&(3, Difficulty::Hard)];
+        assert_eq!(
+            (hard.solved, hard.moves, hard.par, hard.hints),
+            (2, 20, 20, 1)
+        );
+        assert_eq!(hard.best_seconds, Some(30.25));
+    }
+    #[test]
+    fn parses_the_documented_example() {
+        let stats = Stats::parse(
+            "size 4 medium solved 12 moves 150 par 140 seconds 620.5 best 30.2 hints 3\n\
+             hints 5\n\
+             daily streak 3 best 7 last 20345\n\
+             mistakes refilled 2 swaps 1 scalings 0\n\
+             level 1\n",
+        );
+        let size_stats = &stats.by_size[&(4, Difficulty::Medium)];
+        assert_eq!(size_stats.solved, 12);
+        assert_eq!(size_stats.seconds, 620.5);
+        assert_eq!(size_stats.best_seconds, Some(30.2));
+        assert_eq!(stats.hints_used, 5);
+        assert_eq!(
+            (
+                stats.daily_streak,
+                stats.best_daily_streak,
+                stats.last_daily
+            ),
+            (3, 7, Some(20345))
+        );
+        assert_eq!(stats.refilled_columns, 2);
+        assert_eq!(stats.undone_swaps, 1);
+        assert_eq!(stats.completed_levels, BTreeSet::from([0]));
+    }
+    #[test]
+    fn skips_damaged_lines() {
+        let stats = Stats::parse(
+            "size 2 easy solved 1 seconds -5\n\
+             size 3 easy solved 1 seconds NaN\n\
+             size 4 easy solved 1 seconds inf best 1\n\
+             size 5 easy solved 1 best -inf\n\
+             size 6 easy solved\n\
+             size 7 impossible solved 1\n\
+             size 8 hard solved 2 seconds 9 best 4\n\
+             daily streak NaN\n\
+             level 0\n\
+             nonsense\n",
+        );
+        assert_eq!(
+            stats.by_size.keys().collect::<Vec<_>>(),
+            [&(8, Difficulty::Hard)]
+        );
+        assert_eq!(stats.daily_streak, 0);
+        assert!(stats.completed_levels.is_empty());
+        //Describing what was read can't panic on a bad time.
+        stats.describe();
+    }
+}
@@ -0,0 +1,883 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+//!Parameter mode. The coefficients are polynomials in a parameter k, and the question is which k
+//!give the system a unique solution. Dividing a row by an expression in k only works when it
+//!isn't 0, so every expression divided by is kept as an assumption and compared with the exact
+//!answer once the system is solved.
+use super::*;
+use rand::Rng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use std::fmt::{self, Display, Formatter};
+const MAX_DEGREE: usize = 12;
+///How big the constant term and leading coefficient can be for `Polynomial::rational_roots` to
+///look for roots, since it tries every divisor of both
+const MAX_ROOT_SEARCH: i128 = 1 << 20;
+///A polynomial in k with exact coefficients, lowest power first. It's kept in an array rather than
+///a `Vec` so that it's `Copy` like the other kinds of numbers, so its degree can't be more than
+///`MAX_DEGREE`. Like `Rational`, arithmetic panics if that or a coefficient gets too big, and the
+///`checked_` methods return `None` instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Polynomial {
+    coefficients: [Rational; MAX_DEGREE + 1],
+}
+impl Polynomial {
+    pub fn constant(constant: Rational) -> Self {
+        let mut polynomial = Self::default();
+        polynomial.coefficients[0] = constant;
+        polynomial
+    }
+    pub fn k() -> Self {
+        let mut polynomial = Self::default();
+        polynomial.coefficients[1] = Rational::integer(1);
+        polynomial
+    }
+    ///The highest power of k with a coefficient that isn't 0, or `None` for 0
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients
+            .iter()
+            .rposition(|coefficient| !coefficient.is_zero())
+    }
+    pub fn is_zero(&self) -> bool {
+        self.degree().is_none()
+    }
+    ///Whether the polynomial doesn't depend on k
+    pub fn is_constant(&self) -> bool {
+        self.degree().is_none_or(|degree| degree == 0)
+    }
+    fn leading(&self) -> Rational {
+        self.degree()
+            .map_or(Rational::default(), |degree| self.coefficients[degree])
+    }
+    fn scaled(mut self, factor: Rational) -> Option<Self> {
+        for coefficient in self.coefficients.iter_mut() {
+            *coefficient = coefficient.checked_mul(factor)?;
+        }
+        Some(self)
+    }
+    ///Scales the polynomial so that its leading coefficient is 1.
+    fn monic(self) -> Option<Self> {
+        match self.degree() {
+            Some(_) => self.scaled(Rational::integer(1).checked_div(self.leading())?),
+            None => Some(self),
+        }
+    }
+    pub fn checked_add(mut self, rhs: Self) -> Option<Self> {
+        for (coefficient, rhs_coefficient) in self.coefficients.iter_mut().zip(rhs.coefficients) {
+            *coefficient = coefficient.checked_add(rhs_coefficient)?;
+        }
+        Some(self)
+    }
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(rhs.scaled(Rational::integer(-1))?)
+    }
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        let mut product = Self::default();
+        let (Some(degree), Some(rhs_degree)) = (self.degree(), rhs.degree()) else {
+            return Some(product);
+        };
+        if degree + rhs_degree > MAX_DEGREE {
+            return None;
+        }
+        for i in 0..=degree {
+            for j in 0..=rhs_degree {
+                product.coefficients[i + j] = product.coefficients[i + j]
+                    .checked_add(self.coefficients[i].checked_mul(rhs.coefficients[j])?)?;
+            }
+        }
+        Some(product)
+    }
+    pub fn evaluate(&self, k: Rational) -> Rational {
+        self.checked_evaluate(k).expect(OVERFLOW)
+    }
+    pub fn checked_evaluate(&self, k: Rational) -> Option<Rational> {
+        self.coefficients
+            .iter()
+            .rev()
+            .try_fold(Rational::default(), |total, coefficient| {
+                total.checked_mul(k)?.checked_add(*coefficient)
+            })
+    }
+    ///Long division, returning the quotient and the remainder
+    fn div_rem(self, divisor: Self) -> Option<(Self, Self)> {
+        let divisor_degree = divisor.degree().expect("division by 0");
+        let mut quotient = Self::default();
+        let mut remainder = self;
+        while let Some(degree) = remainder.degree()
+            && degree >= divisor_degree
+        {
+            let mut term = Self::default();
+            term.coefficients[degree - divisor_degree] =
+                remainder.leading().checked_div(divisor.leading())?;
+            quotient = quotient.checked_add(term)?;
+            remainder = remainder.checked_sub(divisor.checked_mul(term)?)?;
+        }
+        Some((quotient, remainder))
+    }
+    ///The monic greatest common divisor by Euclid's algorithm
+    fn gcd(a: Self, b: Self) -> Option<Self> {
+        let (mut a, mut b) = (a, b);
+        while !b.is_zero() {
+            (a, b) = (b, a.div_rem(b)?.1);
+        }
+        a.monic()
+    }
+    ///The fractions that make the polynomial 0, from least to greatest. By the rational root
+    ///theorem, once the coefficients are whole numbers, the numerator of a root divides the
+    ///constant term and the denominator divides the leading coefficient. Returns `None` if either
+    ///of those is more than `MAX_ROOT_SEARCH`, which would take too long to try every divisor of,
+    ///or a number gets too big to check a root exactly.
+    pub fn rational_roots(&self) -> Option<Vec<Rational>> {
+        let Some(degree) = self.degree() else {
+            return Some(Vec::new());
+        };
+        let Some(lowest) = self
+            .coefficients
+            .iter()
+            .position(|coefficient| !coefficient.is_zero())
+        else {
+            return Some(Vec::new());
+        };
+        let common_denominator =
+            self.coefficients[lowest..=degree]
+                .iter()
+                .try_fold(1i128, |common, coefficient| {
+                    (common / gcd(common, coefficient.denominator()))
+                        .checked_mul(coefficient.denominator())
+                })?;
+        let integer = |coefficient: Rational| {
+            coefficient
+                .numerator()
+                .checked_mul(common_denominator / coefficient.denominator())
+                .filter(|integer| integer.abs() <= MAX_ROOT_SEARCH)
+        };
+        let constant = integer(self.coefficients[lowest])?;
+        let leading = integer(self.coefficients[degree])?;
+        let mut roots = Vec::new();
+        if lowest > 0 {
+            roots.push(Rational::default());
+        }
+        for numerator in divisors(constant) {
+            for denominator in divisors(leading) {
+                for root in [
+                    Rational::new(numerator, denominator),
+                    Rational::new(-numerator, denominator),
+                ] {
+                    if self.checked_evaluate(root)?.is_zero() && !roots.contains(&root) {
+                        roots.push(root);
+                    }
+                }
+            }
+        }
+        roots.sort_by(|a, b| a.to_f64().total_cmp(&b.to_f64()));
+        Some(roots)
+    }
+}
+///The positive divisors of a number that isn't 0
+fn divisors(number: i128) -> Vec<i128> {
+    let number = number.abs();
+    let mut divisors = Vec::new();
+    let mut divisor = 1;
+    while divisor * divisor <= number {
+        if number % divisor == 0 {
+            divisors.push(divisor);
+            divisors.push(number / divisor);
+        }
+        divisor += 1;
+    }
+    divisors
+}
+///Writes powers of k with superscripts, like k².
+fn superscript(power: usize) -> String {
+    power
+        .to_string()
+        .chars()
+        .map(|digit| {
+            "⁰¹²³⁴⁵⁶⁷⁸⁹"
+                .chars()
+                .nth(digit.to_digit(10).unwrap() as usize)
+                .unwrap()
+        })
+        .collect()
+}
+impl Display for Polynomial {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let Some(degree) = self.degree() else {
+            return write!(formatter, "0");
+        };
+        for power in (0..=degree).rev() {
+            let coefficient = self.coefficients[power];
+            if coefficient.is_zero() {
+                continue;
+            }
+            let size = if coefficient.numerator() < 0 {
+                write!(formatter, "-")?;
+                -coefficient
+            } else {
+                if power != degree {
+                    write!(formatter, "+")?;
+                }
+                coefficient
+            };
+            //A fraction goes in parentheses so that (1/2)k doesn't look like 1/(2k).
+            match (power, size.is_one(), size.denominator()) {
+                (0, _, _) | (_, false, 1) => write!(formatter, "{}", size)?,
+                (_, false, _) => write!(formatter, "({})", size)?,
+                (_, true, _) => {}
+            }
+            match power {
+                0 => {}
+                1 => write!(formatter, "k")?,
+                _ => write!(formatter, "k{}", superscript(power))?,
+            }
+        }
+        Ok(())
+    }
+}
+impl Neg for Polynomial {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.scaled(Rational::integer(-1)).expect(OVERFLOW)
+    }
+}
+impl Add for Polynomial {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).expect(OVERFLOW)
+    }
+}
+impl Sub for Polynomial {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self.checked_sub(rhs).expect(OVERFLOW)
+    }
+}
+impl Mul for Polynomial {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        self.checked_mul(rhs).expect(OVERFLOW)
+    }
+}
+///A fraction of polynomials in k in lowest terms, with a monic denominator. Arithmetic panics if
+///a polynomial gets too big, like `Polynomial`'s.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RationalFunction {
+    numerator: Polynomial,
+    denominator: Polynomial,
+}
+impl RationalFunction {
+    pub fn new(numerator: Polynomial, denominator: Polynomial) -> Self {
+        Self::checked_new(numerator, denominator).expect(OVERFLOW)
+    }
+    ///Like `new`, but `None` if a polynomial gets too big
+    pub fn checked_new(numerator: Polynomial, denominator: Polynomial) -> Option<Self> {
+        assert!(!denominator.is_zero(), "division by 0");
+        if numerator.is_zero() {
+            return Some(Self::default());
+        }
+        let divisor = Polynomial::gcd(numerator, denominator)?;
+        let numerator = numerator.div_rem(divisor)?.0;
+        let denominator = denominator.div_rem(divisor)?.0;
+        let scale = Rational::integer(1).checked_div(denominator.leading())?;
+        Some(Self {
+            numerator: numerator.scaled(scale)?,
+            denominator: denominator.scaled(scale)?,
+        })
+    }
+    pub fn polynomial(polynomial: Polynomial) -> Self {
+        Self::new(polynomial, Polynomial::constant(Rational::integer(1)))
+    }
+    pub const fn numerator(self) -> Polynomial {
+        self.numerator
+    }
+    ///Whether it has k in the denominator or a coefficient that isn't a whole number
+    pub fn is_fraction(self) -> bool {
+        !self.denominator.is_constant()
+            || self
+                .numerator
+                .coefficients
+                .iter()
+                .any(|coefficient| coefficient.denominator() != 1)
+    }
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Self::checked_new(
+            self.numerator
+                .checked_mul(rhs.denominator)?
+                .checked_add(rhs.numerator.checked_mul(self.denominator)?)?,
+            self.denominator.checked_mul(rhs.denominator)?,
+        )
+    }
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(Self {
+            numerator: rhs.numerator.scaled(Rational::integer(-1))?,
+            denominator: rhs.denominator,
+        })
+    }
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Self::checked_new(
+            self.numerator.checked_mul(rhs.numerator)?,
+            self.denominator.checked_mul(rhs.denominator)?,
+        )
+    }
+    ///`None` if a polynomial gets too big or `rhs` is 0
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None;
+        }
+        Self::checked_new(
+            self.numerator.checked_mul(rhs.denominator)?,
+            self.denominator.checked_mul(rhs.numerator)?,
+        )
+    }
+    ///The value at `k`, or `None` if the denominator is 0 there
+    pub fn evaluate(&self, k: Rational) -> Option<Rational> {
+        let denominator = self.denominator.evaluate(k);
+        (!denominator.is_zero()).then(|| self.numerator.evaluate(k) / denominator)
+    }
+}
+impl Default for RationalFunction {
+    fn default() -> Self {
+        Self {
+            numerator: Polynomial::default(),
+            denominator: Polynomial::constant(Rational::integer(1)),
+        }
+    }
+}
+impl Display for RationalFunction {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        if self.denominator.is_constant() {
+            return write!(formatter, "{}", self.numerator);
+        }
+        let parenthesize = |polynomial: Polynomial| {
+            let terms = polynomial
+                .coefficients
+                .iter()
+                .filter(|coefficient| !coefficient.is_zero())
+                .count();
+            if terms > 1 {
+                format!("({})", polynomial)
+            } else {
+                polynomial.to_string()
+            }
+        };
+        write!(
+            formatter,
+            "{}/{}",
+            parenthesize(self.numerator),
+            parenthesize(self.denominator)
+        )
+    }
+}
+impl Field for RationalFunction {
+    fn is_zero(self) -> bool {
+        self.numerator.is_zero()
+    }
+    fn is_one(self) -> bool {
+        self.numerator == self.denominator
+    }
+    fn is_negative(self) -> bool {
+        false
+    }
+    fn format(self, _number_format: NumberFormat) -> String {
+        self.to_string()
+    }
+}
+impl Neg for RationalFunction {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.numerator, self.denominator)
+    }
+}
+impl Add for RationalFunction {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).expect(OVERFLOW)
+    }
+}
+impl Sub for RationalFunction {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self.checked_sub(rhs).expect(OVERFLOW)
+    }
+}
+impl Mul for RationalFunction {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        self.checked_mul(rhs).expect(OVERFLOW)
+    }
+}
+impl Div for RationalFunction {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        assert!(!rhs.is_zero(), "division by 0");
+        self.checked_div(rhs).expect(OVERFLOW)
+    }
+}
+///Cofactor expansion along the first row
+fn determinant(rows: &[Vec<RationalFunction>]) -> RationalFunction {
+    if rows.is_empty() {
+        return RationalFunction::polynomial(Polynomial::constant(Rational::integer(1)));
+    }
+    (0..rows.len()).fold(RationalFunction::default(), |total, column| {
+        let minor: Vec<Vec<RationalFunction>> = rows[1..]
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != column)
+                    .map(|(_, entry)| *entry)
+                    .collect()
+            })
+            .collect();
+        let term = rows[0][column] * determinant(&minor);
+        if column % 2 == 0 {
+            total + term
+        } else {
+            total - term
+        }
+    })
+}
+///Lists fractions like "1, 2, and 3".
+fn list(values: &[Rational]) -> String {
+    let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+    match values.as_slice() {
+        [] => String::new(),
+        [value] => value.clone(),
+        [first, second] => tr_format("{} or {}", &[first, second]),
+        [rest @ .., last] => tr_format("{}, or {}", &[&rest.join(", "), last]),
+    }
+}
+impl System<RationalFunction> {
+    ///A system whose coefficient matrix is LDU, with whole numbers in L and U and at least one
+    ///k - r on the diagonal of D, so that the determinant is 0 at a few whole numbers k.
+    pub fn random_parameter(size: usize, rng: &mut impl Rng) -> Self {
+        let integer = |integer: i32| {
+            RationalFunction::polynomial(Polynomial::constant(Rational::integer(integer as i128)))
+        };
+        let mut roots: Vec<i32> = (-3..=3).collect();
+        roots.shuffle(rng);
+        let parameters = rng.random_range(1..=size.min(2));
+        let mut diagonal: Vec<RationalFunction> = (0..size)
+            .map(|i| {
+                if i < parameters {
+                    RationalFunction::polynomial(
+                        Polynomial::k() - Polynomial::constant(Rational::integer(roots[i] as i128)),
+                    )
+                } else {
+                    integer(*[-2, -1, 1, 2].choose(rng).unwrap())
+                }
+            })
+            .collect();
+        diagonal.shuffle(rng);
+        let mut triangular = |lower: bool| -> Vec<Vec<RationalFunction>> {
+            (0..size)
+                .map(|i| {
+                    (0..size)
+                        .map(|j| match (i == j, (j < i) == lower) {
+                            (true, _) => integer(1),
+                            (false, true) => integer(rng.random_range(-2..=2)),
+                            (false, false) => integer(0),
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+        let lower = triangular(true);
+        let upper = triangular(false);
+        let mut equations: Vec<Equation<RationalFunction>> = (0..size)
+            .map(|i| {
+                let coefficients = (0..size)
+                    .map(|j| {
+                        (0..size).fold(RationalFunction::default(), |total, middle| {
+                            total + lower[i][middle] * diagonal[middle] * upper[middle][j]
+                        })
+                    })
+                    .collect();
+                Equation::new(coefficients, integer(rng.random_range(-5..=5)))
+            })
+            .collect();
+        equations.shuffle(rng);
+        Self::new(equations)
+    }
+    ///The system with a number put in for k, or `None` if that makes a denominator 0
+    fn evaluate(&self, k: Rational) -> Option<System<Rational>> {
+        let equations = self
+            .equations
+            .iter()
+            .map(|equation| {
+                Some(Equation::new(
+                    equation
+                        .coefficients
+                        .iter()
+                        .map(|coefficient| coefficient.evaluate(k))
+                        .collect::<Option<_>>()?,
+                    equation.solution.evaluate(k)?,
+                ))
+            })
+            .collect::<Option<_>>()?;
+        Some(System::new(equations))
+    }
+    fn determinant(&self) -> RationalFunction {
+        let rows: Vec<Vec<RationalFunction>> = self
+            .equations
+            .iter()
+            .map(|equation| equation.coefficients.clone())
+            .collect();
+        determinant(&rows)
+    }
+}
+///A system with a parameter and what's been assumed about it while solving
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParameterSystem {
+    pub system: System<RationalFunction>,
+    ///The system before any operations, to find the exact answer from
+    start: System<RationalFunction>,
+    ///Every expression in k that a row has been divided by, each assumed not to be 0
+    assumptions: Vec<Polynomial>,
+}
+impl ParameterSystem {
+    pub fn new(system: System<RationalFunction>) -> Self {
+        Self {
+            start: system.clone(),
+            system,
+            assumptions: Vec::new(),
+        }
+    }
+    ///The system after the operation and the expression in k the operation divides by, if it
+    ///depends on k, or `None` if a number would get too big to keep exactly. The expression goes
+    ///with `can_make_coefficient_1`'s check that the coefficient isn't 0: an expression in k isn't
+    ///0 for every k, but dividing by it means assuming k isn't one of the values that make it 0.
+    fn applied(
+        &self,
+        operation: RowOperation,
+    ) -> Option<(System<RationalFunction>, Option<Polynomial>)> {
+        ///Combines each number in `row` with the one in the same place in `with`.
+        fn combine(
+            row: &Equation<RationalFunction>,
+            with: &Equation<RationalFunction>,
+            combine: impl Fn(RationalFunction, RationalFunction) -> Option<RationalFunction>,
+        ) -> Option<Equation<RationalFunction>> {
+            Some(Equation::new(
+                row.coefficients
+                    .iter()
+                    .zip(&with.coefficients)
+                    .map(|(number, with)| combine(*number, *with))
+                    .collect::<Option<_>>()?,
+                combine(row.solution, with.solution)?,
+            ))
+        }
+        let divisor = match operation {
+            RowOperation::SwitchRows(..) => None,
+            RowOperation::MakeCoefficient1(equation, coefficient)
+            | RowOperation::MakeCoefficient0WithRow(_, coefficient, equation) => {
+                let divisor = self.system.equations[equation].coefficients[coefficient].numerator();
+                if divisor.is_constant() {
+                    None
+                } else {
+                    Some(divisor.monic()?)
+                }
+            }
+        };
+        let mut system = self.system.clone();
+        match operation {
+            RowOperation::SwitchRows(a, b) => system.switch_rows(a, b),
+            RowOperation::MakeCoefficient1(row, column) => {
+                let dividend = system.equations[row].coefficients[column];
+                system.equations[row] = combine(
+                    &system.equations[row],
+                    &system.equations[row],
+                    |number, _| number.checked_div(dividend),
+                )?;
+            }
+            RowOperation::MakeCoefficient0WithRow(row, column, with) => {
+                let factor = system.equations[row].coefficients[column]
+                    .checked_div(system.equations[with].coefficients[column])?;
+                system.equations[row] = combine(
+                    &system.equations[row],
+                    &system.equations[with],
+                    |number, with| number.checked_sub(factor.checked_mul(with)?),
+                )?;
+            }
+        }
+        Some((system, divisor))
+    }
+    ///Checks that the operation doesn't make a number too big to keep exactly.
+    pub fn check(&self, operation: RowOperation) -> Result<(), String> {
+        match self.applied(operation) {
+            Some(_) => Ok(()),
+            None => Err(tr(
+                "That would make the numbers too big to keep exactly. Try another way, or click \"New\" to start over.",
+            )
+            .to_string()),
+        }
+    }
+    ///Does the operation and keeps track of what it assumes. It must pass `check`.
+    pub fn apply(&mut self, operation: RowOperation) {
+        let (system, divisor) = self.applied(operation).expect(OVERFLOW);
+        if let Some(divisor) = divisor
+            && !self.assumptions.contains(&divisor)
+        {
+            self.assumptions.push(divisor);
+        }
+        self.system = system;
+    }
+    ///Explains the hint like `explain_hint`, along with what it assumes about k.
+    pub fn explain_hint(&self) -> Option<String> {
+        let operation = self.system.hint()?;
+        let Some((_, divisor)) = self.applied(operation) else {
+            return Some(
+                tr("The next step would make the numbers too big to keep exactly. Click \"New\" to start over.")
+                    .to_string(),
+            );
+        };
+        let mut explanation = explain_hint(&self.system, NumberFormat::default())?;
+        if let Some(divisor) = divisor {
+            match divisor.rational_roots().unwrap_or_default().as_slice() {
+                [] => explanation.push_str(&tr_format(" This assumes {} ≠ 0.", &[&divisor])),
+                roots => explanation.push_str(&tr_format(
+                    " This assumes {} ≠ 0, so k isn't {}.",
+                    &[&divisor, &list(roots)],
+                )),
+            }
+        }
+        Some(explanation)
+    }
+    ///Once the system is solved, compares what was assumed with the exact answer: the system has
+    ///a unique solution exactly when the determinant isn't 0.
+    pub fn describe_solution(&self) -> Option<String> {
+        self.system.solution()?;
+        let mut text = if self.assumptions.is_empty() {
+            tr("Solved without dividing by anything with k in it.").to_string()
+        } else {
+            let assumptions: Vec<String> = self
+                .assumptions
+                .iter()
+                .map(|assumption| format!("{} ≠ 0", assumption))
+                .collect();
+            tr_format("Solved assuming {}.", &[&assumptions.join(tr(" and "))])
+        };
+        let determinant = self.start.determinant().numerator();
+        //Without the roots, the determinant still says when there's a unique solution.
+        let roots = determinant.rational_roots().unwrap_or_default();
+        if roots.is_empty() {
+            text.push_str(&tr_format(
+                " The determinant is {}, so the system has a unique solution whenever that isn't 0.",
+                &[&determinant],
+            ));
+        } else {
+            text.push_str(&tr_format(
+                " The determinant is {}, so the system has a unique solution unless k is {}.",
+                &[&determinant, &list(&roots)],
+            ));
+        }
+        for root in roots.iter() {
+            let Some(system) = self.start.evaluate(*root) else {
+                continue;
+            };
+            let mut solved = system.clone();
+            for operation in system.solve() {
+                solved.apply(operation);
+            }
+            text.push_str(&tr_format(
+                if solved.is_inconsistent() {
+                    " When k = {}, it has no solution."
+                } else {
+                    " When k = {}, it has infinitely many solutions."
+                },
+                &[root],
+            ));
+        }
+        let mut extra: Vec<Rational> = self
+            .assumptions
+            .iter()
+            .flat_map(|assumption| assumption.rational_roots().unwrap_or_default())
+            .filter(|root| !roots.contains(root))
+            .collect();
+        extra.sort_by(|a, b| a.to_f64().total_cmp(&b.to_f64()));
+        extra.dedup();
+        if !extra.is_empty() {
+            text.push_str(&tr_format(
+                " The system still has a unique solution when k is {}, even though these operations assumed it wasn't.",
+                &[&list(&extra)],
+            ));
+        }
+        Some(text)
+    }
+}
+impl Game {
+    ///Puts a system with a parameter k on the board, with 2 or 3 equations so that the
+    ///expressions stay readable.
+    pub fn start_parameter(&self) {
+        let size = self.size.get().clamp(2, 3);
+        let system =
+            ParameterSystem::new(System::random_parameter(size, &mut *self.rng.borrow_mut()));
+        let system = FieldSystem::Parameter(system);
+        self.set_field_system(system);
+        self.hud.set_label(tr(
+            "For which k does the system have a unique solution? Solve it, keeping track of what you divide by.",
+        ));
+        self.hud.set_visible(true);
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    ///The polynomial with these whole number coefficients, lowest power first
+    fn polynomial(coefficients: &[i128]) -> Polynomial {
+        let mut polynomial = Polynomial::default();
+        for (i, coefficient) in coefficients.iter().enumerate() {
+            polynomial.coefficients[i] = Rational::integer(*coefficient);
+        }
+        polynomial
+    }
+    fn function(numerator: &[i128], denominator: &[i128]) -> RationalFunction {
+        RationalFunction::new(polynomial(numerator), polynomial(denominator))
+    }
+    #[test]
+    fn does_polynomial_arithmetic() {
+        let product = polynomial(&[-1, 1]) * polynomial(&[1, 1]);
+        assert_eq!(product, polynomial(&[-1, 0, 1]));
+        assert_eq!(product.to_string(), "k²-1");
+        assert_eq!(product.degree(), Some(2));
+        assert_eq!(
+            product.div_rem(polynomial(&[-1, 1])),
+            Some((polynomial(&[1, 1]), Polynomial::default()))
+        );
+        assert_eq!(
+            polynomial(&[1, 0, 1]).div_rem(polynomial(&[0, 1])),
+            Some((polynomial(&[0, 1]), polynomial(&[1])))
+        );
+        assert_eq!(
+            Polynomial::gcd(polynomial(&[-2, 2]), product),
+            Some(polynomial(&[-1, 1]))
+        );
+        assert_eq!(
+            Polynomial::gcd(polynomial(&[1, 2, 1]), polynomial(&[2, 1])),
+            Some(polynomial(&[1]))
+        );
+        assert_eq!(
+            polynomial(&[-1, -1, 2]).rational_roots(),
+            Some(vec![Rational::new(-1, 2), Rational::integer(1)])
+        );
+        assert_eq!(
+            polynomial(&[0, 3]).rational_roots(),
+            Some(vec![Rational::integer(0)])
+        );
+        assert_eq!(polynomial(&[1, 0, 1]).rational_roots(), Some(vec![]));
+        assert_eq!(
+            polynomial(&[-MAX_ROOT_SEARCH, 1]).rational_roots(),
+            Some(vec![Rational::integer(MAX_ROOT_SEARCH)])
+        );
+        //Trying every divisor of a number this big would take far too long.
+        assert_eq!(polynomial(&[(1 << 61) - 1, 1]).rational_roots(), None);
+        assert_eq!(product.evaluate(Rational::integer(3)), Rational::integer(8));
+    }
+    #[test]
+    fn keeps_polynomials_from_getting_too_big() {
+        let mut power = [0; MAX_DEGREE + 1];
+        power[MAX_DEGREE] = 1;
+        let highest = polynomial(&power);
+        assert_eq!(
+            highest.checked_mul(polynomial(&[2])),
+            Some(polynomial(&power).scaled(Rational::integer(2)).unwrap())
+        );
+        assert_eq!(highest.checked_mul(polynomial(&[0, 1])), None);
+        assert_eq!(polynomial(&[i128::MAX]).checked_add(polynomial(&[1])), None);
+        assert_eq!(
+            RationalFunction::polynomial(highest).checked_div(function(&[0, 1], &[1, 1])),
+            None
+        );
+    }
+    #[test]
+    #[should_panic(expected = "arithmetic overflow")]
+    fn panics_on_too_big_polynomials() {
+        let _ = polynomial(&[0, 0, 0, 0, 0, 0, 1]) * polynomial(&[0, 0, 0, 0, 0, 0, 0, 1]);
+    }
+    #[test]
+    fn keeps_rational_functions_in_lowest_terms() {
+        //(k²-1)/(2k-2) = (k+1)/2
+        let reduced = function(&[-1, 0, 1], &[-2, 2]);
+        assert_eq!(
+            reduced,
+            RationalFunction::polynomial(polynomial(&[1, 1]).scaled(Rational::new(1, 2)).unwrap())
+        );
+        assert_eq!(reduced.to_string(), "(1/2)k+1/2");
+        assert!(reduced.is_fraction());
+        let over_k = function(&[1], &[0, 1]);
+        assert_eq!(over_k.to_string(), "1/k");
+        assert_eq!(over_k + over_k, function(&[2], &[0, 1]));
+        assert!((function(&[0, 1], &[1, 1]) * function(&[1, 1], &[0, 1])).is_one());
+        assert!((over_k / over_k).is_one());
+        assert!((over_k - over_k).is_zero());
+        assert_eq!(over_k.checked_div(RationalFunction::default()), None);
+        assert_eq!(function(&[0], &[0, 1]), RationalFunction::default());
+        assert!(!function(&[3, 1], &[1]).is_fraction());
+        assert_eq!(
+            function(&[1, 1], &[0, 1]).evaluate(Rational::integer(2)),
+            Some(Rational::new(3, 2))
+        );
+        assert_eq!(over_k.evaluate(Rational::integer(0)), None);
+    }
+    #[test]
+    fn keeps_track_of_assumptions() {
+        //kx + y = 1 and x + y = 2, which has a unique solution unless k = 1
+        let mut system = ParameterSystem::new(System::new(vec![
+            Equation::new(
+                vec![function(&[0, 1], &[1]), function(&[1], &[1])],
+                function(&[1], &[1]),
+            ),
+            Equation::new(
+                vec![function(&[1], &[1]), function(&[1], &[1])],
+                function(&[2], &[1]),
+            ),
+        ]));
+        assert_eq!(system.describe_solution(), None);
+        assert!(
+            system
+                .explain_hint()
+                .unwrap()
+                .ends_with("This assumes k ≠ 0, so k isn't 0.")
+        );
+        while let Some(operation) = system.system.hint() {
+            assert_eq!(system.check(operation), Ok(()));
+            system.apply(operation);
+        }
+        let description = system.describe_solution().unwrap();
+        assert!(
+            description.starts_with("Solved assuming k ≠ 0 and k-1 ≠ 0."),
+            "{}",
+            description
+        );
+        assert!(description.contains("unless k is 1"), "{}", description);
+        assert!(
+            description.contains("When k = 1, it has no solution."),
+            "{}",
+            description
+        );
+    }
+    #[test]
+    fn rejects_moves_that_get_too_big() {
+        //Taking row 1 from row 2 after dividing it by k⁷ would need k¹⁴.
+        let mut power = [0; 8];
+        power[7] = 1;
+        let mut system = ParameterSystem::new(System::new(vec![
+            Equation::new(
+                vec![function(&power, &[1]), function(&[1], &[1])],
+                function(&[1], &[1]),
+            ),
+            Equation::new(
+                vec![function(&[1], &[1]), function(&power, &[1])],
+                function(&[1], &[1]),
+            ),
+        ]));
+        system.apply(RowOperation::MakeCoefficient1(0, 0));
+        let too_big = RowOperation::MakeCoefficient0WithRow(1, 0, 0);
+        assert_eq!(system.system.hint(), Some(too_big));
+        let before = system.clone();
+        assert!(system.check(too_big).is_err());
+        assert!(system.explain_hint().unwrap().contains("too big"));
+        assert_eq!(system, before);
+        let field_system = FieldSystem::Parameter(system);
+        assert!(field_system.check(too_big).is_err());
+        assert_eq!(field_system.check(RowOperation::SwitchRows(0, 1)), Ok(()));
+    }
+}
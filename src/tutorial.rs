@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+use super::*;
+struct TutorialStep {
+    operation: RowOperation,
+    instruction: &'static str,
+    ///Why the operation doesn't change the answer
+    explanation: &'static str,
+}
+const TUTORIAL_STEPS: [TutorialStep; 4] = [
+    TutorialStep {
+        operation: RowOperation::SwitchRows(0, 1),
+        instruction: "The top left coefficient is 0, and 0 can't be made into 1. Drag from the first row's circle to the second row's circle to swap the rows.",
+        explanation: "Swapping rows only changes the order the equations are written in, so they're still true for the same answer.",
+    },
+    TutorialStep {
+        operation: RowOperation::MakeCoefficient1(0, 0),
+        instruction: "Click the 2 in the top left to divide the first row by 2.",
+        explanation: "Dividing both sides of an equation by the same number that isn't 0 keeps it true for the same answer.",
+    },
+    TutorialStep {
+        operation: RowOperation::MakeCoefficient1(1, 1),
+        instruction: "The first column is done. Click the 2 in the second row to make it 1 too.",
+        explanation: "Same as before: both sides are divided by 2.",
+    },
+    TutorialStep {
+        operation: RowOperation::MakeCoefficient0WithRow(0, 1, 1),
+        instruction: "Drag from the second row's circle to the 2 in the first row to subtract 2 times the second row from the first row.",
+        explanation: "If two equations are true, subtracting a multiple of one from the other gives another true equation, and the one that was used is still there.",
+    },
+];
+fn tutorial_system() -> System {
+    System::new(vec![
+        Equation::new(vec![0.0, 2.0], 6.0),
+        Equation::new(vec![2.0, 4.0], 10.0),
+    ])
+}
+///Checks if two operations do the same thing, since switching rows works either way around.
+fn same_operation(a: RowOperation, b: RowOperation) -> bool {
+    match (a, b) {
+        (RowOperation::SwitchRows(a1, a2), RowOperation::SwitchRows(b1, b2)) => {
+            (a1, a2) == (b1, b2) || (a1, a2) == (b2, b1)
+        }
+        _ => a == b,
+    }
+}
+///What the player has to click or drag between to do `operation`
+fn operation_items(operation: RowOperation) -> Vec<CanvasItem> {
+    match operation {
+        RowOperation::SwitchRows(a, b) => vec![CanvasItem::Circle(a), CanvasItem::Circle(b)],
+        RowOperation::MakeCoefficient1(equation, coefficient) => {
+            vec![CanvasItem::Coefficient(equation, coefficient)]
+        }
+        RowOperation::MakeCoefficient0WithRow(equation, coefficient, with) => vec![
+            CanvasItem::Circle(with),
+            CanvasItem::Coefficient(equation, coefficient),
+        ],
+    }
+}
+impl Game {
+    ///Puts the tutorial's system on the board and shows the first step.
+    pub fn start_tutorial(&self) {
+        self.stop_run();
+        self.set_system(tutorial_system(), None);
+        self.tutorial_step.set(Some(0));
+        self.show_tutorial_step(None);
+    }
+    ///Shows the instruction for the current step with the explanation for the step before it, and
+    ///points out what to click or drag.
+    fn show_tutorial_step(&self, previous: Option<&TutorialStep>) {
+        let Some(index) = self.tutorial_step.get() else {
+            return;
+        };
+        let mut text = String::new();
+        if let Some(previous) = previous {
+            text.push_str(tr(previous.explanation));
+            text.push_str("\n\n");
+        }
+        match TUTORIAL_STEPS.get(index) {
+            Some(step) => {
+                text.push_str(&tr_format(
+                    "Tutorial step {} of {}\n{}",
+                    &[&(index + 1), &TUTORIAL_STEPS.len(), &tr(step.instruction)],
+                ));
+                self.hint.set(Some(step.operation));
+            }
+            None => {
+                text.push_str(tr("The coefficients are the identity matrix, so the answer is x1 = -1 and x2 = 3. That's all there is to it! Click \"New\" for a random system or \"Levels\" for more practice."));
+                self.tutorial_step.set(None);
+                self.hint.set(None);
+            }
+        }
+        self.hud.set_label(&text);
+        self.hud.set_visible(true);
+        self.drawing_area.queue_draw();
+    }
+    ///Checks if `operation` is the one the tutorial is asking for.
+    pub(crate) fn check_tutorial(&self, operation: RowOperation) -> Result<(), String> {
+        match self
+            .tutorial_step
+            .get()
+            .and_then(|index| TUTORIAL_STEPS.get(index))
+        {
+            Some(step) if !same_operation(step.operation, operation) => Err(tr(
+                "That's not the step the tutorial is on. Follow the highlighted squares.",
+            )
+            .to_string()),
+            _ => Ok(()),
+        }
+    }
+    ///Moves on to the next step after the current one is done. Anything else, like the solver
+    ///taking over, ends the tutorial since its steps wouldn't match the board anymore.
+    pub(crate) fn advance_tutorial(&self, operation: RowOperation) {
+        let Some(index) = self.tutorial_step.get() else {
+            return;
+        };
+        match TUTORIAL_STEPS.get(index) {
+            Some(step) if same_operation(step.operation, operation) => {
+                self.tutorial_step.set(Some(index + 1));
+                self.show_tutorial_step(Some(step));
+            }
+            _ => {
+                self.tutorial_step.set(None);
+                self.hint.set(None);
+                self.hud.set_label(tr("The tutorial ended because the board changed another way. Click \"Start Tutorial\" in Help to try again."));
+                self.hud.set_visible(true);
+                self.drawing_area.queue_draw();
+            }
+        }
+    }
+    ///The items to highlight on the board for the current tutorial step
+    pub fn tutorial_highlights(&self) -> Vec<CanvasItem> {
+        match self
+            .tutorial_step
+            .get()
+            .and_then(|index| TUTORIAL_STEPS.get(index))
+        {
+            Some(step) => operation_items(step.operation),
+            None => Vec::new(),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn steps_solve_the_system() {
+        let mut system = tutorial_system();
+        for step in TUTORIAL_STEPS.iter() {
+            assert!(system.should_apply(step.operation), "{}", step.instruction);
+            system.apply(step.operation);
+        }
+        //The last message gives this answer.
+        assert_eq!(system.solution(), Some(vec![-1.0, 3.0]));
+    }
+    #[test]
+    fn switching_rows_works_either_way_around() {
+        assert!(same_operation(
+            RowOperation::SwitchRows(0, 1),
+            RowOperation::SwitchRows(1, 0)
+        ));
+        assert!(!same_operation(
+            RowOperation::MakeCoefficient0WithRow(0, 1, 1),
+            RowOperation::MakeCoefficient0WithRow(1, 1, 0)
+        ));
+    }
+}
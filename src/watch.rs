@@ -0,0 +1,208 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright 2025 UxuginPython
+use super::*;
+use gtk4::Scale;
+use std::time::Duration;
+const DEFAULT_STEPS_PER_SECOND: f64 = 2.0;
+///Solves the system with every strategy and says how far off each answer is from the exact one,
+///or how far off it makes the equations if the exact answer isn't known.
+fn compare_strategies(system: &System, answers: Option<&[f64]>) -> String {
+    let mut lines = Vec::new();
+    for strategy in PivotStrategy::ALL {
+        let operations = system.solve_with(strategy);
+        let mut solved = system.clone();
+        for operation in operations.iter() {
+            solved.apply(*operation);
+        }
+        let accuracy = match (solved.solution(), answers) {
+            (Some(solution), Some(answers)) => {
+                let error = solution
+                    .iter()
+                    .zip(answers)
+                    .map(|(x, answer)| (x - answer).abs())
+                    .fold(0.0, f64::max);
+                tr_format("error {}", &[&format!("{:.1e}", error)])
+            }
+            (Some(solution), None) => tr_format(
+                "residual {}",
+                &[&format!("{:.1e}", system.residual(&solution))],
+            ),
+            (None, _) => tr("no unique solution").to_string(),
+        };
+        lines.push(tr_format(
+            "{}: {} operations, {}",
+            &[&tr(strategy.name()), &operations.len(), &accuracy],
+        ));
+    }
+    lines.join("\n")
+}
+fn update_comparison(label: &Label, game: &Game) {
+    label.set_label(&compare_strategies(
+        &game.start_system.borrow(),
+        game.answers.borrow().as_deref(),
+    ));
+}
+///The state of the watch window while the computer plays
+struct AutoPlay {
+    game: Rc<Game>,
+    player: RefCell<AutoPlayer>,
+    ///Only there while playing
+    timer: RefCell<Option<glib::SourceId>>,
+    play_button: Button,
+    speed: Scale,
+    last_operation: Label,
+    comparison: Label,
+}
+impl AutoPlay {
+    ///Does the solver's next operation. Returns false if the system is already solved.
+    fn step(&self) -> bool {
+        let operation = self
+            .player
+            .borrow_mut()
+            .next_operation(&self.game.system.borrow());
+        match operation {
+            Some(operation) => {
+                self.last_operation.set_label(&describe_operation(
+                    &self.game.system.borrow(),
+                    operation,
+                    self.game.number_format.get(),
+                ));
+                self.game.assisted.set(true);
+                self.game.end_run_for_computer();
+                self.game.end_level_for_computer();
+                self.game.do_operation(operation);
+                true
+            }
+            None => {
+                self.last_operation.set_label(tr("Done"));
+                update_comparison(&self.comparison, &self.game);
+                false
+            }
+        }
+    }
+    fn pause(&self) {
+        if let Some(timer) = self.timer.borrow_mut().take() {
+            timer.remove();
+        }
+        self.play_button.set_label(tr("Play"));
+    }
+    ///Starts playing at the current speed, or restarts if it's already playing.
+    fn play(self: &Rc<Self>) {
+        self.pause();
+        self.play_button.set_label(tr("Pause"));
+        let interval = Duration::from_secs_f64(1.0 / self.speed.value());
+        let my_self = Rc::clone(self);
+        let source = glib::timeout_add_local(interval, move || {
+            if my_self.step() {
+                glib::ControlFlow::Continue
+            } else {
+                //Returning Break removes the timer, so it can't be removed again in pause.
+                my_self.timer.borrow_mut().take();
+                my_self.play_button.set_label(tr("Play"));
+                glib::ControlFlow::Break
+            }
+        });
+        *self.timer.borrow_mut() = Some(source);
+    }
+    fn is_playing(&self) -> bool {
+        self.timer.borrow().is_some()
+    }
+}
+///Opens a window for watching the computer solve the system on the board one operation at a time
+///with a chosen pivot strategy and comparing how much floating point error each strategy ends up
+///with.
+pub fn show_watch_window(game: &Rc<Game>) {
+    let main_box = gtk4::Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(10)
+        .margin_top(10)
+        .margin_bottom(10)
+        .margin_start(10)
+        .margin_end(10)
+        .build();
+    let strategy_names = PivotStrategy::ALL.map(|strategy| tr(strategy.name()));
+    let strategy_drop_down = DropDown::from_strings(&strategy_names);
+    let button_box = gtk4::Box::builder()
+        .orientation(Orientation::Horizontal)
+        .build();
+    let play_button = Button::builder().label(tr("Play")).build();
+    let step_button = Button::builder().label(tr("Step")).build();
+    let ill_conditioned_button = Button::builder()
+        .label(tr("Ill-Conditioned System"))
+        .build();
+    let speed_label = Label::builder().label(tr("Steps per second")).build();
+    let speed = Scale::with_range(Orientation::Horizontal, 0.5, 10.0, 0.5);
+    speed.set_value(DEFAULT_STEPS_PER_SECOND);
+    speed.set_draw_value(true);
+    let last_operation = Label::new(None);
+    let explanation = Label::builder()
+        .wrap(true)
+        .label(tr("The error is the biggest difference between the computer's answer and the exact one. The residual is how far off the computer's answer makes the original equations when the exact answer isn't known. Both come from rounding in floating point arithmetic."))
+        .build();
+    let comparison = Label::builder().selectable(true).build();
+    update_comparison(&comparison, game);
+    let auto_play = Rc::new(AutoPlay {
+        game: Rc::clone(game),
+        player: RefCell::new(AutoPlayer::new(PivotStrategy::default())),
+        timer: RefCell::new(None),
+        play_button: play_button.clone(),
+        speed: speed.clone(),
+        last_operation: last_operation.clone(),
+        comparison: comparison.clone(),
+    });
+    let my_auto_play = Rc::clone(&auto_play);
+    strategy_drop_down.connect_selected_notify(move |drop_down| {
+        let strategy = PivotStrategy::ALL[drop_down.selected() as usize];
+        *my_auto_play.player.borrow_mut() = AutoPlayer::new(strategy);
+    });
+    let my_auto_play = Rc::clone(&auto_play);
+    play_button.connect_clicked(move |_| {
+        if my_auto_play.is_playing() {
+            my_auto_play.pause();
+        } else {
+            my_auto_play.play();
+        }
+    });
+    let my_auto_play = Rc::clone(&auto_play);
+    step_button.connect_clicked(move |_| {
+        my_auto_play.pause();
+        my_auto_play.step();
+    });
+    let my_auto_play = Rc::clone(&auto_play);
+    speed.connect_value_changed(move |_| {
+        if my_auto_play.is_playing() {
+            my_auto_play.play();
+        }
+    });
+    let my_auto_play = Rc::clone(&auto_play);
+    ill_conditioned_button.connect_clicked(move |_| {
+        my_auto_play.pause();
+        let game = &my_auto_play.game;
+        game.end_run_for_computer();
+        let (system, answers) =
+            System::ill_conditioned(game.size.get(), &mut *game.rng.borrow_mut());
+        game.set_system(system, Some(answers));
+        my_auto_play.last_operation.set_label("");
+        update_comparison(&my_auto_play.comparison, game);
+    });
+    button_box.append(&play_button);
+    button_box.append(&step_button);
+    button_box.append(&ill_conditioned_button);
+    main_box.append(&strategy_drop_down);
+    main_box.append(&button_box);
+    main_box.append(&speed_label);
+    main_box.append(&speed);
+    main_box.append(&last_operation);
+    main_box.append(&comparison);
+    main_box.append(&explanation);
+    let window = gtk4::Window::builder()
+        .title(tr("Watch the Computer Solve"))
+        .child(&main_box)
+        .default_width(400)
+        .build();
+    window.connect_close_request(move |_| {
+        auto_play.pause();
+        glib::Propagation::Proceed
+    });
+    window.present();
+}